@@ -0,0 +1,59 @@
+//! Criterion benchmarks for the individual operations (`cargo bench`), for statistically
+//! rigorous per-operation numbers with HTML reports under target/criterion. The binary
+//! stays the end-to-end narrative; this covers sort, filter and group-by only, on an
+//! in-memory frame shaped like the generated data (id, category, value).
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use polars::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const ROWS: usize = 100_000;
+const SEED: u64 = 42;
+
+/// The id / category / value columns the stages below touch, with the binary's default
+/// value range, built in memory. Not the generator's exact data: there is no timestamp
+/// column and the random draws come in a different order, so rows don't match for a seed.
+fn generated_frame() -> DataFrame {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let ids: Vec<i64> = (0..ROWS as i64).collect();
+    let categories: Vec<String> = (0..ROWS).map(|_| format!("Category{}", rng.gen_range(1..=5))).collect();
+    let values: Vec<f64> = (0..ROWS).map(|_| rng.gen_range(0.0..1000.0)).collect();
+    DataFrame::new(vec![
+        Series::new("id", ids),
+        Series::new("category", categories),
+        Series::new("value", values),
+    ])
+    .expect("Failed to build benchmark frame")
+}
+
+fn operations(c: &mut Criterion) {
+    let df = generated_frame();
+    let mut group = c.benchmark_group("operations");
+
+    group.bench_function("sort", |b| {
+        b.iter(|| black_box(df.clone().lazy().sort(["value"], Default::default()).collect().unwrap()))
+    });
+    group.bench_function("filter", |b| {
+        b.iter(|| black_box(df.clone().lazy().filter(col("value").gt(lit(500.0))).collect().unwrap()))
+    });
+    group.bench_function("groupby", |b| {
+        b.iter(|| {
+            black_box(
+                df.clone()
+                    .lazy()
+                    .group_by([col("category")])
+                    .agg([
+                        col("id").mean().alias("id_mean"),
+                        col("value").mean().alias("value_mean"),
+                    ])
+                    .collect()
+                    .unwrap(),
+            )
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, operations);
+criterion_main!(benches);
@@ -1,17 +1,155 @@
 use polars::prelude::*;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::Deserialize;
 use std::fs::File;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
 use std::io::{BufWriter, Write};
 use std::time::{Duration, Instant};
+use sysinfo::System;
 use windows::{
     Win32::System::ProcessStatus::{K32GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS_EX},
-    Win32::System::Threading::GetCurrentProcess,
+    Win32::System::Threading::{GetCurrentProcess, GetProcessIoCounters, IO_COUNTERS},
 };
 
+/// Set when the `--output` report goes to stdout: the human-readable log (progress line,
+/// `[BENCH]` markers included) then moves to stderr so stdout carries only the report
+static LOG_TO_STDERR: AtomicBool = AtomicBool::new(false);
+
+/// `println!` for the human-readable log, honouring `LOG_TO_STDERR`
+macro_rules! say {
+    ($($arg:tt)*) => {
+        if $crate::LOG_TO_STDERR.load(std::sync::atomic::Ordering::Relaxed) {
+            eprintln!($($arg)*)
+        } else {
+            println!($($arg)*)
+        }
+    };
+}
+
+/// `print!` counterpart of `say!`
+macro_rules! say_inline {
+    ($($arg:tt)*) => {
+        if $crate::LOG_TO_STDERR.load(std::sync::atomic::Ordering::Relaxed) {
+            eprint!($($arg)*)
+        } else {
+            print!($($arg)*)
+        }
+    };
+}
+
+fn flush_log() {
+    if LOG_TO_STDERR.load(Ordering::Relaxed) {
+        let _ = std::io::stderr().flush();
+    } else {
+        let _ = std::io::stdout().flush();
+    }
+}
+
+/// Heap accounting via a counting wrapper around the system allocator (`--features profiling`)
+#[cfg(feature = "profiling")]
+mod profiling {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+    static FREED: AtomicUsize = AtomicUsize::new(0);
+    static CURRENT: AtomicUsize = AtomicUsize::new(0);
+    static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+    pub struct CountingAllocator;
+
+    fn record_alloc(size: usize) {
+        ALLOCATED.fetch_add(size, Ordering::Relaxed);
+        let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(current, Ordering::Relaxed);
+    }
+
+    fn record_dealloc(size: usize) {
+        FREED.fetch_add(size, Ordering::Relaxed);
+        CURRENT.fetch_sub(size, Ordering::Relaxed);
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc(layout);
+            if !ptr.is_null() {
+                record_alloc(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc_zeroed(layout);
+            if !ptr.is_null() {
+                record_alloc(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout);
+            record_dealloc(layout.size());
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let new_ptr = System.realloc(ptr, layout, new_size);
+            if !new_ptr.is_null() {
+                record_dealloc(layout.size());
+                record_alloc(new_size);
+            }
+            new_ptr
+        }
+    }
+
+    /// Heap counters, in bytes
+    #[derive(Debug, Clone, Copy)]
+    pub struct HeapStats {
+        pub allocated: usize,
+        pub freed: usize,
+        pub current: usize,
+        pub peak: usize,
+    }
+
+    pub fn snapshot() -> HeapStats {
+        HeapStats {
+            allocated: ALLOCATED.load(Ordering::Relaxed),
+            freed: FREED.load(Ordering::Relaxed),
+            current: CURRENT.load(Ordering::Relaxed),
+            peak: PEAK.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Restarts peak tracking from the current live heap size, so the next peak is per-stage
+    pub fn reset_peak() {
+        PEAK.store(CURRENT.load(Ordering::Relaxed), Ordering::Relaxed);
+    }
+
+    /// Prints heap activity since `before` (alongside the OS memory metrics)
+    pub fn print_heap(stage: &str, before: HeapStats) {
+        let after = snapshot();
+        let mb = |bytes: usize| bytes as f64 / 1024.0 / 1024.0;
+        say!("🧮 {} heap: allocated {:.1} MB, freed {:.1} MB, peak {:.1} MB (live now {:.1} MB)",
+                 stage,
+                 mb(after.allocated - before.allocated),
+                 mb(after.freed - before.freed),
+                 mb(after.peak),
+                 mb(after.current));
+    }
+}
+
+#[cfg(feature = "profiling")]
+#[global_allocator]
+static GLOBAL: profiling::CountingAllocator = profiling::CountingAllocator;
+
 #[derive(Debug)]
 struct MemoryMetrics {
     working_set_mb: u64,
     private_usage_mb: u64,
+    private_usage_bytes: u64,
     pagefile_usage_mb: u64,
     peak_working_set_mb: u64,
 }
@@ -32,6 +170,7 @@ fn get_memory_metrics() -> MemoryMetrics {
             MemoryMetrics {
                 working_set_mb: (mem_counters.WorkingSetSize / 1024 / 1024) as u64,
                 private_usage_mb: (mem_counters.PrivateUsage / 1024 / 1024) as u64,
+                private_usage_bytes: mem_counters.PrivateUsage as u64,
                 pagefile_usage_mb: (mem_counters.PagefileUsage / 1024 / 1024) as u64,
                 peak_working_set_mb: (mem_counters.PeakWorkingSetSize / 1024 / 1024) as u64,
             }
@@ -39,6 +178,7 @@ fn get_memory_metrics() -> MemoryMetrics {
             MemoryMetrics {
                 working_set_mb: 0,
                 private_usage_mb: 0,
+                private_usage_bytes: 0,
                 pagefile_usage_mb: 0,
                 peak_working_set_mb: 0,
             }
@@ -49,168 +189,3672 @@ fn get_memory_metrics() -> MemoryMetrics {
 /// Prints comprehensive memory usage after a given stage
 fn print_memory_detailed(stage: &str) {
     let metrics = get_memory_metrics();
-    println!("📌 After {}: ", stage);
-    println!("   • Working Set: {} MB (physical RAM currently used)", metrics.working_set_mb);
-    println!("   • Private Usage: {} MB (actual process allocation)", metrics.private_usage_mb);
-    println!("   • Pagefile Usage: {} MB (virtual memory used)", metrics.pagefile_usage_mb);
-    println!("   • Peak Working Set: {} MB (highest physical RAM usage)", metrics.peak_working_set_mb);
+    say!("📌 After {}: ", stage);
+    say!("   • Working Set: {} MB (physical RAM currently used)", metrics.working_set_mb);
+    say!("   • Private Usage: {} MB (actual process allocation)", metrics.private_usage_mb);
+    say!("   • Pagefile Usage: {} MB (virtual memory used)", metrics.pagefile_usage_mb);
+    say!("   • Peak Working Set: {} MB (highest physical RAM usage)", metrics.peak_working_set_mb);
+}
+
+/// How often `peak_private_during` samples private usage
+const MEMORY_SAMPLE_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Runs `op` while a sampler thread polls private usage, returning its result, the peak
+/// growth above the starting private usage (bytes) and the elapsed time
+fn peak_private_during<T>(op: impl FnOnce() -> T) -> (T, u64, Duration) {
+    use std::sync::atomic::AtomicU64;
+
+    let baseline = get_memory_metrics().private_usage_bytes;
+    let peak = AtomicU64::new(baseline);
+    let done = AtomicBool::new(false);
+    let (value, elapsed) = std::thread::scope(|scope| {
+        scope.spawn(|| {
+            while !done.load(Ordering::Relaxed) {
+                peak.fetch_max(get_memory_metrics().private_usage_bytes, Ordering::Relaxed);
+                std::thread::sleep(MEMORY_SAMPLE_INTERVAL);
+            }
+        });
+        let start = Instant::now();
+        let value = op();
+        let elapsed = start.elapsed();
+        peak.fetch_max(get_memory_metrics().private_usage_bytes, Ordering::Relaxed);
+        done.store(true, Ordering::Relaxed);
+        (value, elapsed)
+    });
+    (value, peak.into_inner().saturating_sub(baseline), elapsed)
+}
+
+/// Returns the bytes read by the current process so far, from the OS I/O counters.
+/// On Windows this is ReadTransferCount (includes page-cache hits), on Linux the
+/// `read_bytes` field of /proc/self/io (only reads that reached the storage layer).
+#[cfg(windows)]
+fn get_io_read_bytes() -> Option<u64> {
+    unsafe {
+        let mut counters = IO_COUNTERS::default();
+        GetProcessIoCounters(GetCurrentProcess(), &mut counters)
+            .ok()
+            .map(|_| counters.ReadTransferCount)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn get_io_read_bytes() -> Option<u64> {
+    let io = std::fs::read_to_string("/proc/self/io").ok()?;
+    io.lines()
+        .find_map(|line| line.strip_prefix("read_bytes:"))
+        .and_then(|v| v.trim().parse().ok())
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+fn get_io_read_bytes() -> Option<u64> {
+    None
+}
+
+/// Prints the bytes read between two I/O counter samples
+fn print_io_read(stage: &str, before: Option<u64>, trials: usize) {
+    match (before, get_io_read_bytes()) {
+        (Some(before), Some(after)) => {
+            let bytes = after.saturating_sub(before);
+            say!("💽 {}: {:.2} MB read ({:.2} MB per trial)",
+                     stage, bytes as f64 / 1024.0 / 1024.0,
+                     bytes as f64 / trials as f64 / 1024.0 / 1024.0);
+        }
+        _ => say!("💽 {}: I/O counters unavailable", stage),
+    }
+}
+
+/// Prints each column's estimated in-memory size (largest first) and share of the frame
+fn print_column_memory(df: &DataFrame) {
+    let mut sizes: Vec<(&str, &DataType, usize)> = df
+        .get_columns()
+        .iter()
+        .map(|s| (s.name(), s.dtype(), s.estimated_size()))
+        .collect();
+    sizes.sort_by_key(|s| std::cmp::Reverse(s.2));
+    let total = df.estimated_size().max(1);
+    say!("   Column memory (estimated, {:.2} MB total):", total as f64 / 1024.0 / 1024.0);
+    for (name, dtype, bytes) in sizes {
+        say!("     {:<16} {:<10} {:>9.2} MB ({:>5.1}%)",
+                 name, dtype.to_string(), bytes as f64 / 1024.0 / 1024.0, 100.0 * bytes as f64 / total as f64);
+    }
+}
+
+/// Calibration kernel time on the reference machine the speed factor is relative to
+const CALIBRATION_REFERENCE: Duration = Duration::from_millis(50);
+const CALIBRATION_RUNS: usize = 5;
+
+/// Times a fixed single-threaded integer/float kernel (best of CALIBRATION_RUNS) and
+/// returns it as a multiple of CALIBRATION_REFERENCE
+fn calibrate_machine() -> f64 {
+    let kernel = || {
+        let (mut x, mut acc) = (0x9E37_79B9_7F4A_7C15u64, 0.0f64);
+        for _ in 0..20_000_000u32 {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            acc += (x >> 11) as f64 * 1e-16;
+        }
+        std::hint::black_box(acc)
+    };
+    let best = (0..CALIBRATION_RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            kernel();
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or(CALIBRATION_REFERENCE);
+    best.as_secs_f64() / CALIBRATION_REFERENCE.as_secs_f64()
 }
 
 /// Simple RAM usage for quick monitoring (backwards compatibility)
 fn print_ram(stage: &str) {
     let metrics = get_memory_metrics();
-    println!("📌 After {}: RAM = {} MB (Working Set), Private = {} MB",
+    say!("📌 After {}: RAM = {} MB (Working Set), Private = {} MB",
              stage, metrics.working_set_mb, metrics.private_usage_mb);
 }
 
-/// Times an operation multiple times and returns average duration
-fn time_operation<F, T>(operation: F, trials: usize, name: &str) -> (T, Duration)
-where
-    F: Fn() -> PolarsResult<T>,
-{
-    let mut durations = Vec::new();
-    let mut result = None;
+/// Hardware/OS context of the machine producing the results
+#[derive(Debug, Clone)]
+struct EnvironmentInfo {
+    cpu_model: String,
+    logical_cores: usize,
+    physical_cores: Option<usize>,
+    total_ram_mb: u64,
+    os: String,
+    /// `--baseline-machine-normalize`: calibration kernel time relative to the reference
+    /// machine (> 1.0 means slower); stage times divided by it share a common scale
+    machine_factor: Option<f64>,
+}
 
-    for _ in 0..trials {
-        let start = Instant::now();
-        let op_result = operation().expect("Operation failed");
-        durations.push(start.elapsed());
-        result = Some(op_result);
+impl EnvironmentInfo {
+    fn capture() -> Self {
+        let mut sys = System::new();
+        sys.refresh_cpu();
+        sys.refresh_memory();
+        EnvironmentInfo {
+            cpu_model: sys
+                .cpus()
+                .first()
+                .map(|cpu| cpu.brand().trim().to_string())
+                .unwrap_or_else(|| "unknown".to_string()),
+            logical_cores: sys.cpus().len(),
+            physical_cores: sys.physical_core_count(),
+            total_ram_mb: sys.total_memory() / 1024 / 1024,
+            os: System::long_os_version().unwrap_or_else(|| std::env::consts::OS.to_string()),
+            machine_factor: None,
+        }
+    }
+
+    fn print(&self) {
+        say!("🖥️  Environment:");
+        say!("   • CPU: {}", self.cpu_model);
+        match self.physical_cores {
+            Some(physical) => say!("   • Cores: {} logical / {} physical", self.logical_cores, physical),
+            None => say!("   • Cores: {} logical", self.logical_cores),
+        }
+        say!("   • RAM: {} MB", self.total_ram_mb);
+        say!("   • OS: {}", self.os);
+        if let Some(factor) = self.machine_factor {
+            say!("   • Machine speed factor: {:.3} (1.0 = reference machine, higher = slower)", factor);
+        }
+        say!();
+    }
+}
+
+/// Timing record for one measured stage, kept for end-of-run accounting
+#[derive(Debug, Clone)]
+struct StageTiming {
+    name: String,
+    trials: usize,
+    avg: Duration,
+    /// Working set right after the stage finished
+    working_set_mb: u64,
+    /// Smallest / largest working set sampled after each trial
+    working_set_range_mb: (u64, u64),
+    /// Private-usage growth over the stage divided by the dataset row count
+    bytes_per_row: Option<f64>,
+    /// Every successful trial's duration in run order, including rejected outliers
+    samples: Vec<Duration>,
+    /// Set when a trial failed; the other fields then cover only the completed trials
+    error: Option<String>,
+}
+
+/// One-line `[BENCH] key=value ...` record for log scrapers, printed for every stage in
+/// every output mode. `op` is the stage name lowercased with runs of other characters
+/// collapsed to `_` (e.g. `groupby_aggregate`); keys are only ever added, not renamed.
+fn bench_marker(timing: &StageTiming) -> String {
+    let mut op = String::new();
+    for c in timing.name.chars() {
+        if c.is_ascii_alphanumeric() {
+            op.push(c.to_ascii_lowercase());
+        } else if !op.is_empty() && !op.ends_with('_') {
+            op.push('_');
+        }
     }
+    let op = op.trim_end_matches('_');
+    match &timing.error {
+        Some(_) => format!("[BENCH] op={} status=failed trials={}", op, timing.trials),
+        None => format!(
+            "[BENCH] op={} status=ok avg_ms={:.3} ws_mb={} trials={}",
+            op, timing.avg.as_secs_f64() * 1000.0, timing.working_set_mb, timing.trials
+        ),
+    }
+}
 
-    let avg_duration = durations.iter().sum::<Duration>() / trials as u32;
-    println!("✅ {} completed in (avg of {} runs): {:.3?}", name, trials, avg_duration);
+/// Copy of every completed stage, shared with the Ctrl-C handler so an interrupted
+/// run can still report what it measured
+static COMPLETED_STAGES: Mutex<Vec<StageTiming>> = Mutex::new(Vec::new());
 
-    (result.unwrap(), avg_duration)
+/// Prints the stages completed so far and writes the `--output` report for an aborted run
+fn emit_partial_report(reason: &str, output: OutputFormat, report: &str, environment: &EnvironmentInfo) {
+    // Finish the interrupted progress line
+    say!();
+    let completed = COMPLETED_STAGES.lock().map(|g| g.clone()).unwrap_or_default();
+    say!("\n⚠️  {} - {} completed stage(s):", reason, completed.len());
+    for t in &completed {
+        match &t.error {
+            Some(e) => say!("   • {}: failed ({})", t.name, e),
+            None => say!("   • {}: {:.3?} (avg of {} runs)", t.name, t.avg, t.trials),
+        }
+    }
+    if let Some(content) = render_report(output, &completed, environment, &[]) {
+        emit_report(report, &content);
+    }
+}
+
+/// On Ctrl-C, prints the stages completed so far, writes the `--output` report (with the
+/// run's `environment`, so a partial report matches a full one), and exits
+fn install_interrupt_handler(output: OutputFormat, report: String, environment: EnvironmentInfo) {
+    let result = ctrlc::set_handler(move || {
+        emit_partial_report("Interrupted", output, &report, &environment);
+        std::process::exit(130);
+    });
+    if let Err(e) = result {
+        eprintln!("⚠️  Could not install Ctrl-C handler: {}", e);
+    }
 }
 
-fn main() -> PolarsResult<()> {
-    println!("🚀 Starting Polars Performance Benchmark\n");
+/// Run-wide state shared by every measured stage
+struct BenchRun {
+    started: Instant,
+    timings: Vec<StageTiming>,
+    /// Suppresses the per-trial progress indicator
+    quiet: bool,
+    /// When set, stages keep running past their trial count until timings stabilize
+    adaptive: Option<AdaptiveTrials>,
+    environment: EnvironmentInfo,
+    /// `--cooldown-ms`: idle time before each stage (and each trial with
+    /// `--cooldown-between-trials`), never inside the timed window
+    cooldown: Duration,
+    cooldown_between_trials: bool,
+    /// Replace trials slower than median + 3×MAD with reruns (`--rerun-until-stable`)
+    reject_outliers: bool,
+    /// Failure policy: record failed stages and keep going instead of aborting
+    continue_on_failure: bool,
+    /// Rows in the loaded dataset, used to normalize memory deltas per row
+    dataset_rows: Option<usize>,
+    /// `--op-timeout`: longest a single trial may run before it is recorded as failed
+    op_timeout: Option<Duration>,
+    /// Files written by this run, removed at the end under `--cleanup`
+    artifacts: Vec<PathBuf>,
+}
 
-    // 1. Generate CSV with buffered writing
-    println!("📝 Generating CSV data...");
-    let start = Instant::now();
-    let mut rng = rand::thread_rng();
-    let file = File::create("data.csv").expect("Failed to create CSV file");
+/// Stopping rule for adaptive trial counts: stop once the coefficient of variation
+/// over the last `trials` timings is at most `cv_threshold`, or after `max_trials`
+#[derive(Debug, Clone, Copy)]
+struct AdaptiveTrials {
+    cv_threshold: f64,
+    max_trials: usize,
+}
+
+impl BenchRun {
+    fn new(cli: &CliArgs) -> Self {
+        BenchRun {
+            started: Instant::now(),
+            timings: Vec::new(),
+            quiet: cli.quiet,
+            adaptive: cli.adaptive.then_some(AdaptiveTrials {
+                cv_threshold: cli.cv_threshold,
+                max_trials: cli.max_trials,
+            }),
+            environment: EnvironmentInfo::capture(),
+            cooldown: cli.cooldown,
+            cooldown_between_trials: cli.cooldown_between_trials,
+            reject_outliers: cli.rerun_until_stable,
+            continue_on_failure: cli.continue_on_failure,
+            dataset_rows: None,
+            op_timeout: cli.op_timeout,
+            artifacts: Vec::new(),
+        }
+    }
+
+    fn register_artifact(&mut self, path: &str) {
+        let path = PathBuf::from(path);
+        if !self.artifacts.contains(&path) {
+            self.artifacts.push(path);
+        }
+    }
+
+    /// Deletes every registered artifact, reporting (but not failing on) errors.
+    /// Directories are only removed once empty, so register them after their files.
+    fn cleanup_artifacts(&self) {
+        for path in &self.artifacts {
+            let removed = if path.is_dir() {
+                std::fs::remove_dir(path)
+            } else {
+                std::fs::remove_file(path)
+            };
+            match removed {
+                Ok(()) => say!("🧹 Removed {}", path.display()),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => eprintln!("⚠️  Could not remove {}: {}", path.display(), e),
+            }
+        }
+    }
+
+    /// Stores a finished (or failed) stage, also sharing it with the Ctrl-C handler
+    fn record(&mut self, timing: StageTiming) {
+        say!("{}", bench_marker(&timing));
+        if let Ok(mut completed) = COMPLETED_STAGES.lock() {
+            completed.push(timing.clone());
+        }
+        self.timings.push(timing);
+    }
+
+    /// Runs a group of dependent stages under the failure policy. With `--continue` a
+    /// failure is reported and `None` returned so the run moves on to the next group;
+    /// with `--fail-fast` (the default) the error aborts the whole run. Failures outside
+    /// `time_operation` (checks, file writes) are recorded as a failed stage named after
+    /// the group, so they show up in the report like failed trials do.
+    fn stage_group<T>(
+        &mut self,
+        group: &str,
+        body: impl FnOnce(&mut BenchRun) -> PolarsResult<T>,
+    ) -> PolarsResult<Option<T>> {
+        let recorded = self.timings.len();
+        let outcome = body(self);
+        if let Err(e) = &outcome {
+            if !self.timings[recorded..].iter().any(|t| t.error.is_some()) {
+                self.record(StageTiming {
+                    name: group.to_string(),
+                    trials: 0,
+                    avg: Duration::ZERO,
+                    working_set_mb: get_memory_metrics().working_set_mb,
+                    working_set_range_mb: (0, 0),
+                    bytes_per_row: None,
+                    samples: Vec::new(),
+                    error: Some(e.to_string()),
+                });
+            }
+        }
+        match outcome {
+            Ok(value) => Ok(Some(value)),
+            Err(e) if self.continue_on_failure => {
+                say!("⚠️  Skipping rest of '{}' after failure: {}", group, e);
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Total time spent inside measured operations: every timed trial of every stage,
+    /// including warmup, rejected outliers and the trials of stages that later failed
+    fn measured_total(&self) -> Duration {
+        self.timings
+            .iter()
+            .flat_map(|t| t.samples.iter())
+            .sum()
+    }
+}
+
+/// Failure with enough context to report (or handle, when used as a library) without
+/// digging through panic strings
+enum BenchError {
+    /// File system failure, with what was being attempted
+    Io { context: String, source: std::io::Error },
+    /// A polars operation failed
+    Polars(PolarsError),
+    /// Invalid configuration: config file contents, filter expression or option combination
+    Config(String),
+}
+
+impl std::fmt::Display for BenchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BenchError::Io { context, source } => write!(f, "{}: {}", context, source),
+            BenchError::Polars(e) => write!(f, "polars: {}", e),
+            BenchError::Config(message) => write!(f, "config: {}", message),
+        }
+    }
+}
+
+/// `main` returns `BenchError`, and a failing `main` prints its `Debug` form; delegating to
+/// `Display` keeps that a readable message instead of the nested enum
+impl std::fmt::Debug for BenchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self, f)
+    }
+}
+
+impl std::error::Error for BenchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BenchError::Io { source, .. } => Some(source),
+            BenchError::Polars(e) => Some(e),
+            BenchError::Config(_) => None,
+        }
+    }
+}
+
+impl From<PolarsError> for BenchError {
+    fn from(e: PolarsError) -> Self {
+        BenchError::Polars(e)
+    }
+}
+
+impl BenchError {
+    /// `map_err` adapter attaching `context` to an I/O error
+    fn io(context: impl Into<String>) -> impl FnOnce(std::io::Error) -> BenchError {
+        let context = context.into();
+        move |source| BenchError::Io { context, source }
+    }
+}
+
+/// Benchmark settings, optionally loaded from a JSON file passed via `--config <path>`
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct BenchConfig {
+    /// Existing CSV to benchmark; when unset a synthetic data.csv is generated
+    input: Option<String>,
+    id_column: String,
+    group_key: String,
+    value_column: String,
+    /// Int64 epoch-millisecond column used by the time-series stages (skipped if absent or
+    /// another type; sorted first if out of order)
+    timestamp_column: String,
+    filter_threshold: f64,
+    /// Predicate string (e.g. `value > 500`) replacing the threshold filter
+    filter: Option<String>,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        BenchConfig {
+            input: None,
+            id_column: "id".to_string(),
+            group_key: "category".to_string(),
+            value_column: "value".to_string(),
+            timestamp_column: "timestamp".to_string(),
+            filter_threshold: 500.0,
+            filter: None,
+        }
+    }
+}
+
+impl BenchConfig {
+    fn data_path(&self) -> &str {
+        self.input.as_deref().unwrap_or("data.csv")
+    }
+
+    /// Checks the CSV header at `path` has the id, group-key and value columns before any
+    /// stage runs, naming what is missing and what was found instead
+    fn validate_input(&self, path: &str) -> PolarsResult<()> {
+        let schema = LazyCsvReader::new(path).with_has_header(true).finish()?.schema()?;
+        let missing: Vec<&str> = [&self.id_column, &self.group_key, &self.value_column]
+            .into_iter()
+            .map(String::as_str)
+            .filter(|name| schema.get(name).is_none())
+            .collect();
+        if !missing.is_empty() {
+            let found: Vec<String> = schema.iter().map(|(name, dtype)| format!("{} ({})", name, dtype)).collect();
+            polars_bail!(
+                ColumnNotFound: "{} is missing expected column(s) {:?}; found {}. \
+                Map your columns with id_column / group_key / value_column in a --config file.",
+                path, missing, found.join(", ")
+            );
+        }
+        Ok(())
+    }
+
+    /// The filter stage predicate: the `filter` string if set, else `value_column > filter_threshold`
+    fn filter_expr(&self) -> Result<Expr, BenchError> {
+        match &self.filter {
+            Some(text) => parse_filter(text).map_err(|e| BenchError::Config(format!("invalid filter {:?}: {}", text, e))),
+            None => Ok(col(&self.value_column).gt(lit(self.filter_threshold))),
+        }
+    }
+}
+
+/// Parses a small predicate language into a polars expression:
+/// `<column> <op> <literal>` clauses (ops: > >= < <= == !=) joined by `and` / `or`,
+/// where `and` binds tighter. Literals are numbers or double-quoted strings,
+/// e.g. `value >= 100 and value < 200 or category == "Category1"`.
+fn parse_filter(text: &str) -> Result<Expr, String> {
+    split_unquoted(text, " or ")
+        .into_iter()
+        .map(|any| {
+            split_unquoted(any, " and ")
+                .into_iter()
+                .map(parse_comparison)
+                .reduce(|a, b| Ok(a?.and(b?)))
+                .unwrap()
+        })
+        .reduce(|a, b| Ok(a?.or(b?)))
+        .unwrap()
+}
+
+/// Splits `text` on `separator`, ignoring occurrences inside double-quoted literals
+fn split_unquoted<'a>(text: &'a str, separator: &str) -> Vec<&'a str> {
+    let mut parts = Vec::new();
+    let (mut start, mut in_quotes) = (0, false);
+    for (pos, ch) in text.char_indices() {
+        if ch == '"' {
+            in_quotes = !in_quotes;
+        } else if !in_quotes && pos >= start && text[pos..].starts_with(separator) {
+            parts.push(&text[start..pos]);
+            start = pos + separator.len();
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+fn parse_comparison(clause: &str) -> Result<Expr, String> {
+    const OPS: [&str; 6] = [">=", "<=", "==", "!=", ">", "<"];
+    let (pos, op) = OPS
+        .iter()
+        .filter_map(|op| clause.find(op).map(|pos| (pos, *op)))
+        .min_by_key(|(pos, _)| *pos)
+        .ok_or_else(|| format!("no comparison operator in {:?}", clause))?;
+    let name = clause[..pos].trim();
+    let raw = clause[pos + op.len()..].trim();
+    if name.is_empty() || raw.is_empty() {
+        return Err(format!("expected `<column> {} <literal>` in {:?}", op, clause));
+    }
+
+    let value = if let Some(s) = raw.strip_prefix('"').and_then(|r| r.strip_suffix('"')) {
+        lit(s)
+    } else {
+        lit(raw.parse::<f64>().map_err(|_| format!("{:?} is not a number or quoted string", raw))?)
+    };
+    let column = col(name);
+    Ok(match op {
+        ">=" => column.gt_eq(value),
+        "<=" => column.lt_eq(value),
+        "==" => column.eq(value),
+        "!=" => column.neq(value),
+        ">" => column.gt(value),
+        _ => column.lt(value),
+    })
+}
+
+/// Machine-readable report emitted after the human-readable log (`--output <format>`),
+/// written to the `--report` destination
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OutputFormat {
+    /// Human-readable log only
+    Text,
+    /// `operation<TAB>avg_ms<TAB>working_set_mb`, one line per stage; on stdout these are
+    /// the only lines, the log goes to stderr
+    Tsv,
+    Json,
+    Markdown,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "tsv" => Ok(OutputFormat::Tsv),
+            "json" => Ok(OutputFormat::Json),
+            "markdown" | "md" => Ok(OutputFormat::Markdown),
+            other => Err(format!("unknown output format: {}", other)),
+        }
+    }
+}
+
+/// Command-line arguments
+#[derive(Debug, Clone)]
+struct CliArgs {
+    config: Option<String>,
+    /// `--input` paths; the first overrides the config file's input
+    inputs: Vec<String>,
+    /// Run the suite on both `--input` files and print a side-by-side table
+    compare_files: bool,
+    filter: Option<String>,
+    /// Also stream the full pipeline to out.parquet via `sink_parquet`
+    sink: bool,
+    /// Group-by result CSV written by another engine (e.g. pandas) to check against ours
+    reference_result: Option<String>,
+    /// Relative tolerance for float columns in the equivalence check
+    tolerance: f64,
+    /// Check the operation stages against the hand-verified golden dataset, then exit
+    verify_golden: bool,
+    /// Run only this stage in a loop for `duration` (for attaching an external profiler)
+    profile_stage: Option<String>,
+    duration: Duration,
+    /// Build this many value columns and benchmark melting them back to one
+    wide: Option<usize>,
+    /// `--continue`: keep running independent stages after a failure (`--fail-fast` is the default)
+    continue_on_failure: bool,
+    /// Also group by the id column (cardinality == row count)
+    append_id_as_category: bool,
+    /// Also benchmark a read capped at this many rows
+    limit: Option<usize>,
+    /// Also compare the CSV read in default vs low_memory reader mode
+    low_memory: bool,
+    /// Also write the data with comma, tab and pipe separators and time reading each
+    compare_separators: bool,
+    /// Also benchmark reading a copy with quoted, comma/newline-embedding category fields
+    /// (generated data only)
+    quoted_fields: bool,
+    /// `--read-chunk-size`: CSV reader chunk sizes to sweep the full read across
+    read_chunk_sizes: Vec<usize>,
+    /// `--concurrency-test [--concurrent <n>]`: collect the lazy pipeline from n threads at once
+    concurrency: Option<usize>,
+    /// Also run the lazy pipeline with pushdowns, simplification and CSE disabled
+    no_optimize: bool,
+    /// Also split the data into this many CSV shards and benchmark a glob scan over them
+    shards: Option<usize>,
+    /// Also benchmark sort/filter on a deliberately fragmented frame, before and after rechunk
+    chunked: bool,
+    /// Delete generated files at the end (`--cleanup`); kept by default (`--keep-artifacts`)
+    cleanup: bool,
+    quiet: bool,
+    /// Fraction of generated `value` cells left empty (null), 0.0..=1.0
+    null_rate: f64,
+    /// Seed for the data generator; fixed seeds give identical data (and result checksums)
+    seed: Option<u64>,
+    /// Rows written by the generator
+    rows: usize,
+    /// `--row-sweep`: rerun generation + suite at each of these row counts, then print a scaling table
+    row_sweep: Vec<usize>,
+    /// Range of generated values; with the 500.0 default threshold this sets filter selectivity
+    value_min: f64,
+    value_max: f64,
+    /// Restrict generated values to this many distinct levels (tie-heavy sort keys)
+    distinct_values: Option<usize>,
+    /// Decimal places written for the value column
+    precision: Option<usize>,
+    /// Pad category labels to this many (partly multibyte) chars; 0 keeps `CategoryN`
+    category_width: usize,
+    /// Report format; a non-text report sent to stdout moves the log to stderr
+    output: OutputFormat,
+    /// Where the `--output` report goes: `-` for stdout, otherwise a file path
+    report: String,
+    /// Print each stage's trial sequence and its timing trend
+    iterations_report: bool,
+    /// Also write the data as Parquet and compare scanning all columns vs only the value column
+    parquet_pruning: bool,
+    /// Write every trial's duration (long format CSV) to this path
+    raw_timings: Option<String>,
+    /// Also write bench_plot.dat + bench_plot.gp for a gnuplot bar chart
+    comparison_plot: bool,
+    /// Fail the stage if a single trial takes longer than this
+    op_timeout: Option<Duration>,
+    /// Run the lazy pipeline twice on identically seeded data and diff the results, then exit
+    verify_determinism: bool,
+    /// Let `--verify-determinism` pass when the runs differ only in row order
+    ignore_order: bool,
+    /// Compare peak memory of the eager chain against the lazy pipeline, then exit
+    compare_lazy_eager_memory: bool,
+    /// Time commands typed on stdin against the loaded frame instead of running the suite
+    repl: bool,
+    /// Regenerate the data before each of this many pipeline trials, then exit
+    seed_per_trial: Option<usize>,
+    /// Sleep between stages so the CPU can cool down: longer wall-clock time for fewer
+    /// throttling-skewed measurements on thermally constrained machines
+    cooldown: Duration,
+    cooldown_between_trials: bool,
+    /// Reject outlier trials (median + 3×MAD) and rerun to replace them
+    rerun_until_stable: bool,
+    /// Calibrate a machine speed factor at startup and also report normalized times
+    normalize: bool,
+    /// Auto-tune trial counts (see `AdaptiveTrials`)
+    adaptive: bool,
+    cv_threshold: f64,
+    max_trials: usize,
+}
+
+impl Default for CliArgs {
+    fn default() -> Self {
+        CliArgs {
+            config: None,
+            inputs: Vec::new(),
+            compare_files: false,
+            filter: None,
+            sink: false,
+            cleanup: false,
+            chunked: false,
+            shards: None,
+            no_optimize: false,
+            concurrency: None,
+            limit: None,
+            read_chunk_sizes: Vec::new(),
+            quoted_fields: false,
+            compare_separators: false,
+            low_memory: false,
+            append_id_as_category: false,
+            continue_on_failure: false,
+            wide: None,
+            reference_result: None,
+            verify_golden: false,
+            profile_stage: None,
+            duration: Duration::from_secs(30),
+            tolerance: 1e-9,
+            quiet: false,
+            null_rate: 0.0,
+            seed: None,
+            rows: 100_000,
+            row_sweep: Vec::new(),
+            value_min: 0.0,
+            value_max: 1000.0,
+            distinct_values: None,
+            precision: None,
+            category_width: 0,
+            output: OutputFormat::Text,
+            report: "-".to_string(),
+            iterations_report: false,
+            parquet_pruning: false,
+            raw_timings: None,
+            comparison_plot: false,
+            op_timeout: None,
+            compare_lazy_eager_memory: false,
+            verify_determinism: false,
+            ignore_order: false,
+            repl: false,
+            seed_per_trial: None,
+            cooldown: Duration::ZERO,
+            cooldown_between_trials: false,
+            rerun_until_stable: false,
+            normalize: false,
+            adaptive: false,
+            cv_threshold: 0.05,
+            max_trials: 30,
+        }
+    }
+}
+
+/// Threads used by `--concurrency-test` when `--concurrent` is not given
+const DEFAULT_CONCURRENT_THREADS: usize = 4;
+
+/// Command-line mistakes are configuration errors
+fn usage(message: impl Into<String>) -> BenchError {
+    BenchError::Config(message.into())
+}
+
+/// Takes the argument following a flag, failing with `message` if there is none
+fn next_arg(args: &mut impl Iterator<Item = String>, message: &str) -> Result<String, BenchError> {
+    args.next().ok_or_else(|| usage(message))
+}
+
+/// Parses the value following `flag`, failing with a usage message if missing or malformed
+fn next_value<T: std::str::FromStr>(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<T, BenchError> {
+    args.next()
+        .and_then(|v| v.parse().ok())
+        .ok_or_else(|| usage(format!("{} requires a valid {} value", flag, std::any::type_name::<T>())))
+}
+
+/// Parses the number of seconds following `flag` (non-negative, finite)
+fn next_seconds(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<Duration, BenchError> {
+    let secs: f64 = next_value(args, flag)?;
+    Duration::try_from_secs_f64(secs).map_err(|_| usage(format!("{} requires a non-negative number of seconds, got {}", flag, secs)))
+}
+
+/// Parses a positive count following a flag, failing with `message` otherwise
+fn next_positive(args: &mut impl Iterator<Item = String>, message: &str) -> Result<usize, BenchError> {
+    args.next()
+        .and_then(|v| v.parse().ok())
+        .filter(|&n: &usize| n > 0)
+        .ok_or_else(|| usage(message))
+}
+
+/// Parses the comma-separated positive integers following `flag`
+fn next_positive_list(args: &mut impl Iterator<Item = String>, flag: &str, message: &str) -> Result<Vec<usize>, BenchError> {
+    next_arg(args, message)?
+        .split(',')
+        .map(|v| {
+            v.trim()
+                .parse::<usize>()
+                .ok()
+                .filter(|&n| n > 0)
+                .ok_or_else(|| usage(format!("{} requires positive integers, got {:?}", flag, v)))
+        })
+        .collect()
+}
+
+fn parse_args() -> Result<CliArgs, BenchError> {
+    let mut cli = CliArgs::default();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--config" => cli.config = Some(next_arg(&mut args, "--config requires a path")?),
+            "--input" => cli.inputs.push(next_arg(&mut args, "--input requires a path")?),
+            "--compare-files" => cli.compare_files = true,
+            "--filter" => cli.filter = Some(next_arg(&mut args, "--filter requires an expression")?),
+            "--sink" => cli.sink = true,
+            "--pandas-result" => cli.reference_result = Some(next_arg(&mut args, "--pandas-result requires a path")?),
+            "--tolerance" => cli.tolerance = next_value(&mut args, "--tolerance")?,
+            "--verify-golden" => cli.verify_golden = true,
+            "--profile-stage" => cli.profile_stage = Some(next_arg(&mut args, "--profile-stage requires a stage name")?),
+            "--duration" => cli.duration = next_seconds(&mut args, "--duration")?,
+            "--wide" => cli.wide = Some(next_value(&mut args, "--wide")?),
+            "--fail-fast" => cli.continue_on_failure = false,
+            "--continue" => cli.continue_on_failure = true,
+            "--append-id-as-category" => cli.append_id_as_category = true,
+            "--limit" => cli.limit = Some(next_value(&mut args, "--limit")?),
+            "--low-memory" => cli.low_memory = true,
+            "--compare-separators" => cli.compare_separators = true,
+            "--quoted-fields" => cli.quoted_fields = true,
+            "--read-chunk-size" => cli.read_chunk_sizes.extend(next_positive_list(
+                &mut args,
+                "--read-chunk-size",
+                "--read-chunk-size requires a size or comma-separated sizes",
+            )?),
+            "--chunked" => cli.chunked = true,
+            "--concurrency-test" => {
+                cli.concurrency.get_or_insert(DEFAULT_CONCURRENT_THREADS);
+            }
+            "--concurrent" => cli.concurrency = Some(next_positive(&mut args, "--concurrent requires a positive thread count")?),
+            "--no-optimize" => cli.no_optimize = true,
+            "--shards" => cli.shards = Some(next_positive(&mut args, "--shards requires a positive shard count")?),
+            "--cleanup" => cli.cleanup = true,
+            "--keep-artifacts" => cli.cleanup = false,
+            "--quiet" => cli.quiet = true,
+            "--null-rate" => {
+                cli.null_rate = args
+                    .next()
+                    .and_then(|v| v.parse().ok())
+                    .filter(|r| (0.0..=1.0).contains(r))
+                    .ok_or_else(|| usage("--null-rate requires a number between 0 and 1"))?
+            }
+            "--seed" => cli.seed = Some(next_value(&mut args, "--seed")?),
+            "--rows" => cli.rows = next_value(&mut args, "--rows")?,
+            "--row-sweep" => cli.row_sweep.extend(next_positive_list(
+                &mut args,
+                "--row-sweep",
+                "--row-sweep requires comma-separated row counts",
+            )?),
+            "--value-min" => cli.value_min = next_value(&mut args, "--value-min")?,
+            "--value-max" => cli.value_max = next_value(&mut args, "--value-max")?,
+            "--distinct-values" => cli.distinct_values = Some(next_positive(&mut args, "--distinct-values requires a positive count")?),
+            "--precision" => cli.precision = Some(next_value(&mut args, "--precision")?),
+            "--category-width" => cli.category_width = next_value(&mut args, "--category-width")?,
+            "--output" => cli.output = next_value(&mut args, "--output")?,
+            "--report" => cli.report = next_arg(&mut args, "--report requires a path or -")?,
+            "--parquet-pruning" => cli.parquet_pruning = true,
+            "--iterations-report" => cli.iterations_report = true,
+            "--raw-timings" => cli.raw_timings = Some(next_arg(&mut args, "--raw-timings requires a path")?),
+            "--format-comparison-plot" => cli.comparison_plot = true,
+            "--op-timeout" => cli.op_timeout = Some(next_seconds(&mut args, "--op-timeout")?),
+            "--verify-determinism" => cli.verify_determinism = true,
+            "--ignore-order" => cli.ignore_order = true,
+            "--compare-lazy-eager-memory" => cli.compare_lazy_eager_memory = true,
+            "--repl" => cli.repl = true,
+            "--seed-per-trial" => cli.seed_per_trial = Some(next_positive(&mut args, "--seed-per-trial requires a positive trial count")?),
+            "--cooldown-ms" => cli.cooldown = Duration::from_millis(next_value(&mut args, "--cooldown-ms")?),
+            "--cooldown-between-trials" => cli.cooldown_between_trials = true,
+            "--rerun-until-stable" => cli.rerun_until_stable = true,
+            "--baseline-machine-normalize" => cli.normalize = true,
+            "--adaptive" => cli.adaptive = true,
+            "--cv-threshold" => cli.cv_threshold = next_value(&mut args, "--cv-threshold")?,
+            "--max-trials" => cli.max_trials = next_value(&mut args, "--max-trials")?,
+            other => return Err(usage(format!("unknown argument: {}", other))),
+        }
+    }
+    if !(cli.value_min.is_finite() && cli.value_max.is_finite() && cli.value_min < cli.value_max) {
+        return Err(usage(format!("--value-min must be below --value-max (got {}..{})", cli.value_min, cli.value_max)));
+    }
+    // These modes print their own tables and return before the end-of-run reports
+    let mode = if !cli.row_sweep.is_empty() {
+        Some("--row-sweep")
+    } else if cli.compare_files {
+        Some("--compare-files")
+    } else {
+        None
+    };
+    if let Some(mode) = mode {
+        let report_flag = [
+            (cli.output != OutputFormat::Text, "--output"),
+            (cli.raw_timings.is_some(), "--raw-timings"),
+            (cli.comparison_plot, "--format-comparison-plot"),
+            (cli.iterations_report, "--iterations-report"),
+        ].into_iter().find(|(set, _)| *set);
+        if let Some((_, flag)) = report_flag {
+            return Err(usage(format!("{} writes no end-of-run report and can't be combined with {}", mode, flag)));
+        }
+    }
+    Ok(cli)
+}
+
+/// Loads the benchmark config, falling back to the built-in synthetic dataset layout
+fn load_config(cli: &CliArgs) -> Result<BenchConfig, BenchError> {
+    let mut cfg: BenchConfig = match &cli.config {
+        Some(path) => {
+            let text = std::fs::read_to_string(path).map_err(BenchError::io(format!("reading config {}", path)))?;
+            serde_json::from_str(&text).map_err(|e| BenchError::Config(format!("parsing {}: {}", path, e)))?
+        }
+        None => BenchConfig::default(),
+    };
+    if let Some(input) = cli.inputs.first() {
+        cfg.input = Some(input.clone());
+    }
+    if cli.filter.is_some() {
+        cfg.filter = cli.filter.clone();
+    }
+    Ok(cfg)
+}
+
+const IO_RETRY_ATTEMPTS: u32 = 3;
+const IO_RETRY_BACKOFF: Duration = Duration::from_millis(250);
+
+/// Retries a fallible I/O step with increasing backoff (transient locks, e.g. antivirus scans)
+fn with_retry<T, E: std::fmt::Display>(what: &str, mut op: impl FnMut() -> Result<T, E>) -> Result<T, E> {
+    let mut attempt = 1;
+    loop {
+        match op() {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt < IO_RETRY_ATTEMPTS => {
+                say!("⚠️  {} failed (attempt {}/{}): {} - retrying", what, attempt, IO_RETRY_ATTEMPTS, e);
+                std::thread::sleep(IO_RETRY_BACKOFF * attempt);
+                attempt += 1;
+            }
+            Err(e) => {
+                eprintln!("❌ {} failed after {} attempts: {}", what, IO_RETRY_ATTEMPTS, e);
+                return Err(e);
+            }
+        }
+    }
+}
+
+/// First generated timestamp (epoch ms); rows are ~1s apart with sub-second jitter
+const BASE_TIMESTAMP_MS: i64 = 1_700_000_000_000;
+/// Spacing of the as-of join reference frame
+const REFERENCE_INTERVAL_MS: i64 = 5_000;
+
+/// The five category labels. With `width > 0` each label is padded to `width` chars
+/// with multibyte filler placed before the distinguishing digit, so keys share a
+/// long common prefix and hashing/comparison has to scan the whole string.
+fn category_labels(width: usize) -> Vec<String> {
+    const FILLER: [char; 4] = ['é', 'ß', '日', '本'];
+    (1..=5)
+        .map(|k| {
+            let pad = width.saturating_sub("Category".len() + 1);
+            let filler: String = FILLER.iter().cycle().take(pad).collect();
+            format!("Category{}{}", filler, k)
+        })
+        .collect()
+}
+
+/// Generates `--rows` rows of synthetic id,category,value,timestamp CSV with buffered writing;
+/// a `--null-rate` share of value cells is written empty so polars reads them as null,
+/// and `--precision` fixes the decimals written for value (default: shortest round-trip form).
+/// Values are uniform over `--value-min..--value-max` (default 0..1000), or over just
+/// `--distinct-values` evenly spaced levels of it; `seed: None` seeds from entropy.
+fn generate_csv(path: &str, cli: &CliArgs, seed: Option<u64>) -> Result<(), BenchError> {
+    let null_rate = cli.null_rate;
+    let categories = category_labels(cli.category_width);
+    let mut rng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let file = with_retry(&format!("Creating {}", path), || File::create(path))
+        .map_err(BenchError::io(format!("creating {}", path)))?;
     let mut writer = BufWriter::new(file);
+    let write_failed = || BenchError::io(format!("writing {}", path));
 
-    writeln!(writer, "id,category,value").expect("Failed to write header");
-    for i in 0..100_000 {
+    writeln!(writer, "id,category,value,timestamp").map_err(write_failed())?;
+    for i in 0..cli.rows as i64 {
+        let value = if null_rate > 0.0 && rng.gen_bool(null_rate) {
+            String::new()
+        } else {
+            let v: f64 = match cli.distinct_values {
+                Some(levels) => {
+                    let step = (cli.value_max - cli.value_min) / levels as f64;
+                    cli.value_min + step * rng.gen_range(0..levels) as f64
+                }
+                None => rng.gen_range(cli.value_min..cli.value_max),
+            };
+            match cli.precision {
+                Some(decimals) => format!("{:.*}", decimals, v),
+                None => v.to_string(),
+            }
+        };
         writeln!(
             writer,
-            "{},{},{}",
+            "{},{},{},{}",
             i,
-            format!("Category{}", rng.gen_range(1..=5)),
-            rng.gen_range(0.0..1000.0)
-        ).expect("Failed to write data row");
+            categories[rng.gen_range(0..categories.len())],
+            value,
+            BASE_TIMESTAMP_MS + i * 1000 + rng.gen_range(0..1000)
+        ).map_err(write_failed())?;
     }
-    drop(writer); // Ensure buffer is flushed
+    writer.flush().map_err(write_failed())
+}
 
-    println!("✅ CSV generated in: {:.3?}", start.elapsed());
-    print_memory_detailed("CSV Generation");
+/// Copy of the generated CSV written by `write_quoted_csv`
+const QUOTED_DATA_FILE: &str = "data_quoted.csv";
 
-    println!("\n--- Testing Individual Operations (Forced Execution) ---");
+/// Rewrites the generated CSV at `source` with the second (category) field quoted and
+/// carrying an embedded comma, escaped quotes and a newline, e.g.
+/// `"Category3, ""quoted""<LF>label"`. Row count and the other fields are unchanged;
+/// the split assumes the generator's unquoted layout.
+fn write_quoted_csv(source: &str, target: &str) -> std::io::Result<()> {
+    use std::io::BufRead;
 
-    // 2. Read CSV and force execution
-    let (mut df, _) = time_operation(
-        || {
-            LazyCsvReader::new("data.csv")
-                .with_has_header(true)
-                .finish()?
-                .collect()
-        },
-        3,
-        "CSV Read & Load"
-    );
-    print_memory_detailed("CSV Read & Load");
+    let reader = std::io::BufReader::new(File::open(source)?);
+    let mut writer = BufWriter::new(File::create(target)?);
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        if i == 0 {
+            writeln!(writer, "{}", line)?;
+            continue;
+        }
+        let mut fields: Vec<&str> = line.splitn(3, ',').collect();
+        if fields.len() < 2 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("line {} of {} has no second field to quote", i + 1, source),
+            ));
+        }
+        let quoted = format!("\"{}, \"\"quoted\"\"\nlabel\"", fields[1]);
+        fields[1] = &quoted;
+        writeln!(writer, "{}", fields.join(","))?;
+    }
+    writer.flush()
+}
 
-    // 3. Sort (force execution with multiple trials)
-    let (sorted_df, _) = time_operation(
-        || {
-            df.clone().lazy()
-                .sort(["value"], Default::default())
-                .collect()
-        },
-        3,
-        "Sort"
-    );
-    df = sorted_df;
-    print_memory_detailed("Sort");
-
-    // 4. Filter (force execution with multiple trials)
-    let (filtered_df, _) = time_operation(
-        || {
-            df.clone().lazy()
-                .filter(col("value").gt(lit(500.0)))
-                .collect()
-        },
-        3,
-        "Filter"
-    );
-    df = filtered_df;
-    print_memory_detailed("Filter");
-
-    // 5. GroupBy + Aggregate (force execution with multiple trials)
-    let (grouped_df, _) = time_operation(
-        || {
-            df.clone().lazy()
-                .group_by([col("category")])
-                .agg([
-                    col("id").mean().alias("id_mean"),
-                    col("value").mean().alias("value_mean"),
-                ])
-                .collect()
-        },
-        3,
-        "GroupBy + Aggregate"
-    );
-    df = grouped_df;
-    print_memory_detailed("GroupBy + Aggregate");
+/// Builds a sorted reference frame (one row every REFERENCE_INTERVAL_MS) covering `df`'s time span
+fn build_reference_frame(df: &DataFrame, ts_col: &str) -> PolarsResult<DataFrame> {
+    let ts = df.column(ts_col)?.i64()?;
+    let (first, last) = (ts.min().unwrap_or(0), ts.max().unwrap_or(0));
+    let points: Vec<i64> = (first..=last).step_by(REFERENCE_INTERVAL_MS as usize).collect();
+    let ref_values: Vec<f64> = (0..points.len()).map(|i| i as f64).collect();
+    DataFrame::new(vec![
+        Series::new(ts_col, points),
+        Series::new("ref_value", ref_values),
+    ])
+}
+
+/// The Sort stage: the frame in ascending order of the value column
+fn sort_stage(frame: LazyFrame, cfg: &BenchConfig) -> LazyFrame {
+    frame.sort([cfg.value_column.as_str()], Default::default())
+}
 
-    println!("\n--- Testing Optimized Lazy Pipeline ---");
+/// The Filter stage: the rows matching the configured predicate
+fn filter_stage(frame: LazyFrame, predicate: Expr) -> LazyFrame {
+    frame.filter(predicate)
+}
 
-    // Full lazy pipeline (the proper way)
-    let lazy_pipeline = LazyCsvReader::new("data.csv")
-        .with_has_header(true)
-        .finish()?
-        .sort(["value"], Default::default())
-        .filter(col("value").gt(lit(500.0)))
-        .group_by([col("category")])
+/// The GroupBy + Aggregate stage: mean id and mean value per group key
+fn group_by_stage(frame: LazyFrame, cfg: &BenchConfig) -> LazyFrame {
+    let (id_col, value_col) = (cfg.id_column.as_str(), cfg.value_column.as_str());
+    frame
+        .group_by([col(&cfg.group_key)])
         .agg([
-            col("id").mean().alias("id_mean"),
-            col("value").mean().alias("value_mean"),
-        ]);
+            col(id_col).mean().alias(&format!("{}_mean", id_col)),
+            col(value_col).mean().alias(&format!("{}_mean", value_col)),
+        ])
+}
 
-    // Show the optimized plan
-    println!("\n🧠 Optimized Query Plan:");
-    println!("{}", lazy_pipeline.describe_optimized_plan()?);
-
-    // Time the full lazy execution
-    let (lazy_result, _) = time_operation(
-        || lazy_pipeline.clone().collect(),
-        5,
-        "Full Lazy Pipeline"
-    );
-    print_ram("Full Lazy Pipeline");
-
-    println!("\n📊 Final Results:");
-    println!("Individual operations result:\n{}", df);
-    println!("\nLazy pipeline result:\n{}", lazy_result);
-
-    println!("\n📋 SUMMARY:");
-    println!("• Individual operations: Each step forced to execute separately");
-    println!("• Lazy pipeline: All operations optimized and executed together");
-    println!("• Memory metrics explained:");
-    println!("  - Working Set: Physical RAM currently used by process");
-    println!("  - Private Usage: Actual memory allocated to process (most accurate)");
-    println!("  - Pagefile Usage: Virtual memory used (includes swapped memory)");
-    println!("  - Peak Working Set: Highest physical RAM usage during execution");
-    println!("• Timing uses averages across multiple runs for accuracy");
-    println!("• The lazy pipeline should be significantly faster due to optimizations");
-    println!("\n💡 External Profiling Options:");
-    println!("• Process Explorer: Real-time memory monitoring");
-    println!("• Windows Performance Monitor: Detailed system metrics");
-    println!("• For Linux: heaptrack, Valgrind massif");
+/// Sort, filter and group-by chained into the single lazy pipeline
+fn pipeline_stages(frame: LazyFrame, cfg: &BenchConfig, predicate: Expr) -> LazyFrame {
+    group_by_stage(filter_stage(sort_stage(frame, cfg), predicate), cfg)
+}
 
-    Ok(())
-}
\ No newline at end of file
+/// `--verify-golden` seed when `--seed` is not given
+const GOLDEN_SEED: u64 = 0;
+
+/// Hand-checked 8-row dataset in the synthetic layout, its rows shuffled by `seed`. Values
+/// are distinct so the sort order is unambiguous, and the group-by output is compared in
+/// key order, so the expected results below (default `value > 500` filter) hold for every seed.
+fn golden_frame(seed: u64) -> PolarsResult<DataFrame> {
+    let mut rows = [
+        (0i64, "Category1", 100.0),
+        (1, "Category2", 600.0),
+        (2, "Category1", 900.0),
+        (3, "Category3", 550.0),
+        (4, "Category2", 800.0),
+        (5, "Category1", 700.0),
+        (6, "Category3", 200.0),
+        (7, "Category2", 400.0),
+    ];
+    rows.shuffle(&mut StdRng::seed_from_u64(seed));
+    df!(
+        "id" => rows.iter().map(|r| r.0).collect::<Vec<_>>(),
+        "category" => rows.iter().map(|r| r.1).collect::<Vec<_>>(),
+        "value" => rows.iter().map(|r| r.2).collect::<Vec<_>>(),
+    )
+}
+
+const GOLDEN_SORTED_IDS: [i64; 8] = [0, 6, 7, 3, 1, 5, 4, 2];
+const GOLDEN_FILTERED_IDS: [i64; 5] = [3, 1, 5, 4, 2];
+/// (category, mean id, mean value) per group, sorted by category
+const GOLDEN_GROUPS: [(&str, f64, f64); 3] = [
+    ("Category1", 3.5, 800.0),
+    ("Category2", 2.5, 700.0),
+    ("Category3", 3.0, 550.0),
+];
+
+/// Runs the harness's Sort, Filter and GroupBy stages (default config) on `golden`, each
+/// feeding the next; the grouped output is put in key order for comparison
+fn golden_stages(golden: DataFrame) -> PolarsResult<(DataFrame, DataFrame, DataFrame)> {
+    let cfg = BenchConfig::default();
+    let predicate = cfg.filter_expr().map_err(|e| polars_err!(ComputeError: "{}", e))?;
+    let sorted = sort_stage(golden.lazy(), &cfg).collect()?;
+    let filtered = filter_stage(sorted.clone().lazy(), predicate).collect()?;
+    let grouped = group_by_stage(filtered.clone().lazy(), &cfg)
+        .sort([cfg.group_key.as_str()], Default::default())
+        .collect()?;
+    Ok((sorted, filtered, grouped))
+}
+
+fn golden_ids(df: &DataFrame) -> PolarsResult<Vec<Option<i64>>> {
+    Ok(df.column("id")?.i64()?.into_iter().collect())
+}
+
+fn golden_group_rows(df: &DataFrame) -> PolarsResult<Vec<(Option<&str>, Option<f64>, Option<f64>)>> {
+    Ok(df.column("category")?.str()?.into_iter()
+        .zip(df.column("id_mean")?.f64()?.into_iter())
+        .zip(df.column("value_mean")?.f64()?.into_iter())
+        .map(|((c, i), v)| (c, i, v))
+        .collect())
+}
+
+/// Runs the golden stages and checks each result against the hand-computed answer,
+/// failing on the first mismatch
+fn verify_golden(seed: u64) -> PolarsResult<()> {
+    let check = |stage: &str, ok: bool, got: &dyn std::fmt::Debug| -> PolarsResult<()> {
+        if ok {
+            say!("   ✅ {}", stage);
+            Ok(())
+        } else {
+            polars_bail!(ComputeError: "golden {} mismatch: got {:?}", stage, got)
+        }
+    };
+    let (sorted, filtered, grouped) = golden_stages(golden_frame(seed)?)?;
+
+    let sorted_ids = golden_ids(&sorted)?;
+    check("sort", sorted_ids == GOLDEN_SORTED_IDS.map(Some), &sorted_ids)?;
+
+    let filtered_ids = golden_ids(&filtered)?;
+    check("filter", filtered_ids == GOLDEN_FILTERED_IDS.map(Some), &filtered_ids)?;
+
+    let rows = golden_group_rows(&grouped)?;
+    let expected = GOLDEN_GROUPS.map(|(c, i, v)| (Some(c), Some(i), Some(v)));
+    check("group-by", rows == expected, &rows)?;
+    Ok(())
+}
+
+/// Trials slower than median + this many median absolute deviations count as outliers
+const OUTLIER_MAD_FACTOR: f64 = 3.0;
+
+/// Indices of trials slower than median + OUTLIER_MAD_FACTOR × MAD, slowest first.
+/// Needs at least three trials and a nonzero MAD to say anything.
+fn mad_outliers(durations: &[Duration]) -> Vec<usize> {
+    let median = |values: &mut Vec<f64>| {
+        values.sort_by(|a, b| a.total_cmp(b));
+        let mid = values.len() / 2;
+        if values.len().is_multiple_of(2) { (values[mid - 1] + values[mid]) / 2.0 } else { values[mid] }
+    };
+    if durations.len() < 3 {
+        return Vec::new();
+    }
+    let secs: Vec<f64> = durations.iter().map(Duration::as_secs_f64).collect();
+    let med = median(&mut secs.clone());
+    let mad = median(&mut secs.iter().map(|s| (s - med).abs()).collect());
+    if mad == 0.0 {
+        return Vec::new();
+    }
+    let cutoff = med + OUTLIER_MAD_FACTOR * mad;
+    let mut outliers: Vec<usize> = (0..secs.len()).filter(|&i| secs[i] > cutoff).collect();
+    outliers.sort_by(|&a, &b| secs[b].total_cmp(&secs[a]));
+    outliers
+}
+
+/// Coefficient of variation (std dev / mean) of a set of timings
+fn coefficient_of_variation(durations: &[Duration]) -> f64 {
+    let n = durations.len() as f64;
+    let secs: Vec<f64> = durations.iter().map(|d| d.as_secs_f64()).collect();
+    let mean = secs.iter().sum::<f64>() / n;
+    if mean == 0.0 {
+        return 0.0;
+    }
+    let variance = secs.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n;
+    variance.sqrt() / mean
+}
+
+/// Rebuilds `df` from many small slices appended one by one, leaving ~`chunks` chunks per column
+fn fragment_frame(df: &DataFrame, chunks: usize) -> PolarsResult<DataFrame> {
+    let chunk_rows = (df.height() / chunks).max(1);
+    let mut fragmented = df.slice(0, chunk_rows);
+    let mut offset = chunk_rows;
+    while offset < df.height() {
+        fragmented.vstack_mut(&df.slice(offset as i64, chunk_rows))?;
+        offset += chunk_rows;
+    }
+    Ok(fragmented)
+}
+
+/// Rows materialized by the head/tail preview stages
+const PREVIEW_ROWS: IdxSize = 5;
+
+/// Lookup-table size for the join sweep, and the matches per probe row it cycles through
+const JOIN_LOOKUP_ROWS: usize = 4096;
+const JOIN_FANOUTS: [usize; 5] = [1, 2, 4, 8, 16];
+
+/// Rows kept by the top-N stage
+const TOP_N: IdxSize = 100;
+
+/// Share of rows drawn by the sample stage
+const SAMPLE_FRACTION: f64 = 0.1;
+
+/// Periods the lag stage shifts the value column by
+const LAG_ROWS: i64 = 3;
+
+/// Parquet copy of the loaded data written by `--parquet-pruning`
+const PARQUET_DATA_FILE: &str = "data.parquet";
+
+/// Separators written and read back by `--compare-separators`, with their file names
+const SEPARATORS: [(&str, u8, &str); 3] = [
+    ("comma", b',', "data_sep_comma.csv"),
+    ("tab", b'\t', "data_sep_tab.tsv"),
+    ("pipe", b'|', "data_sep_pipe.txt"),
+];
+
+const SHARD_DIR: &str = "data_shards";
+
+/// Splits `df` into `shards` CSV files under SHARD_DIR, returning the written paths
+fn write_shards(df: &DataFrame, shards: usize) -> PolarsResult<Vec<String>> {
+    std::fs::create_dir_all(SHARD_DIR)?;
+    let rows_per_shard = df.height().div_ceil(shards.max(1));
+    let mut paths = Vec::new();
+    for shard in 0..shards {
+        let mut part = df.slice((shard * rows_per_shard) as i64, rows_per_shard);
+        let path = format!("{}/part_{:04}.csv", SHARD_DIR, shard);
+        let file = with_retry(&format!("Creating {}", path), || File::create(&path))?;
+        CsvWriter::new(file).finish(&mut part)?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+/// Runs one trial, returning its outcome and duration. Under `--op-timeout` the trial runs
+/// on a detached worker holding its own handle to the closure (which owns cheap Arc clones
+/// of the frames it reads). Polars queries can't be cancelled, so an overrunning worker is
+/// left to finish in the background, its result discarded, and the trial fails instead.
+fn run_trial<F, T>(operation: &Arc<F>, limit: Option<Duration>) -> (PolarsResult<T>, Duration)
+where
+    F: Fn() -> PolarsResult<T> + Send + Sync + 'static,
+    T: Send + 'static,
+{
+    let Some(limit) = limit else {
+        let start = Instant::now();
+        let outcome = operation.as_ref()();
+        return (outcome, start.elapsed());
+    };
+    let (finished, outcome) = mpsc::channel();
+    let worker = Arc::clone(operation);
+    std::thread::spawn(move || {
+        let start = Instant::now();
+        let outcome = worker.as_ref()();
+        // Nobody is listening any more if the trial already timed out
+        let _ = finished.send((outcome, start.elapsed()));
+    });
+    match outcome.recv_timeout(limit) {
+        Ok(done) => done,
+        Err(mpsc::RecvTimeoutError::Timeout) => (
+            Err(polars_err!(ComputeError: "timed out after {:.3?} (--op-timeout), still running in the background", limit)),
+            limit,
+        ),
+        Err(mpsc::RecvTimeoutError::Disconnected) => (
+            Err(polars_err!(ComputeError: "trial worker panicked")),
+            Duration::ZERO,
+        ),
+    }
+}
+
+/// Times an operation multiple times and returns average duration.
+/// In adaptive mode `trials` is the minimum and the size of the stability window.
+fn time_operation<F, T>(run: &mut BenchRun, operation: F, trials: usize, name: &str) -> PolarsResult<(T, Duration)>
+where
+    F: Fn() -> PolarsResult<T> + Send + Sync + 'static,
+    T: Send + 'static,
+{
+    let operation = Arc::new(operation);
+    let mut durations = Vec::new();
+    let mut result = None;
+    #[cfg(feature = "profiling")]
+    let heap_before = {
+        profiling::reset_peak();
+        profiling::snapshot()
+    };
+
+    let max_trials = run.adaptive.map_or(trials, |a| a.max_trials.max(trials));
+    let window_len = if run.adaptive.is_some() { trials.max(2) } else { trials };
+    let mut stable_cv = None;
+    let mut ws_range = (u64::MAX, 0);
+    if !run.timings.is_empty() && !run.cooldown.is_zero() {
+        std::thread::sleep(run.cooldown);
+    }
+    let private_before = get_memory_metrics().private_usage_bytes;
+    // `--rerun-until-stable`: trials rejected as outliers are replaced, up to `trials` reruns
+    let mut rejected = 0;
+    let mut attempt = 0;
+    let mut samples = Vec::new();
+    while durations.len() < max_trials {
+        attempt += 1;
+        if attempt > 1 && run.cooldown_between_trials {
+            std::thread::sleep(run.cooldown);
+        }
+        if !run.quiet {
+            say_inline!("\r   ⏳ {}: trial {}/{}", name, attempt, max_trials + rejected);
+            flush_log();
+        }
+        let (outcome, elapsed) = run_trial(&operation, run.op_timeout);
+        let op_result = match outcome {
+            Ok(value) => value,
+            Err(e) => {
+                if !run.quiet {
+                    say_inline!("\r{}\r", " ".repeat(name.len() + 32));
+                }
+                say!("❌ {} failed on trial {}: {}", name, attempt, e);
+                run.record(StageTiming {
+                    name: name.to_string(),
+                    trials: durations.len(),
+                    avg: Duration::ZERO,
+                    working_set_mb: get_memory_metrics().working_set_mb,
+                    working_set_range_mb: (0, 0),
+                    bytes_per_row: None,
+                    samples,
+                    error: Some(e.to_string()),
+                });
+                return Err(e);
+            }
+        };
+        durations.push(elapsed);
+        samples.push(elapsed);
+        result = Some(op_result);
+        let ws = get_memory_metrics().working_set_mb;
+        ws_range = (ws_range.0.min(ws), ws_range.1.max(ws));
+
+        if let Some(adaptive) = run.adaptive {
+            if durations.len() >= window_len {
+                let cv = coefficient_of_variation(&durations[durations.len() - window_len..]);
+                if cv <= adaptive.cv_threshold {
+                    stable_cv = Some(cv);
+                    break;
+                }
+            }
+        }
+
+        if run.reject_outliers && durations.len() == max_trials && rejected < trials {
+            let mut outliers = mad_outliers(&durations);
+            outliers.truncate(trials - rejected);
+            outliers.sort_unstable_by(|a, b| b.cmp(a));
+            for index in outliers {
+                durations.remove(index);
+                rejected += 1;
+            }
+        }
+    }
+    if !run.quiet {
+        // Blank out the progress line before the summary is printed over it
+        say_inline!("\r{}\r", " ".repeat(name.len() + 32));
+    }
+
+    // Adaptive runs average only the final (stable) window, excluding warmup trials
+    let window = &durations[durations.len().saturating_sub(window_len)..];
+    let avg_duration = window.iter().sum::<Duration>() / window.len() as u32;
+    let runs = durations.len();
+    say!("✅ {} completed in (avg of {} runs): {:.3?}", name, window.len(), avg_duration);
+    if rejected > 0 {
+        say!("   Rejected {} outlier trial(s) above median + {}×MAD and reran them", rejected, OUTLIER_MAD_FACTOR);
+    }
+    if run.adaptive.is_some() {
+        match stable_cv {
+            Some(cv) => say!("   Stabilized after {} trials (CV {:.1}%)", runs, cv * 100.0),
+            None => say!("   Did not stabilize within {} trials (CV {:.1}%)",
+                             runs, coefficient_of_variation(window) * 100.0),
+        }
+    }
+    if runs > 1 {
+        say!("   Working set across trials: min {} MB / max {} MB (range {} MB)",
+                 ws_range.0, ws_range.1, ws_range.1 - ws_range.0);
+    }
+    let private_delta = get_memory_metrics().private_usage_bytes as i64 - private_before as i64;
+    let bytes_per_row = run.dataset_rows.filter(|&rows| rows > 0).map(|rows| private_delta as f64 / rows as f64);
+    if let Some(per_row) = bytes_per_row {
+        say!("   Private usage delta: {:+.1} MB ({:+.1} bytes/row)",
+                 private_delta as f64 / 1024.0 / 1024.0, per_row);
+    }
+    #[cfg(feature = "profiling")]
+    profiling::print_heap(name, heap_before);
+    let timing = StageTiming {
+        name: name.to_string(),
+        trials: runs,
+        avg: avg_duration,
+        working_set_mb: get_memory_metrics().working_set_mb,
+        working_set_range_mb: ws_range,
+        bytes_per_row,
+        samples,
+        error: None,
+    };
+    run.record(timing);
+
+    Ok((result.unwrap(), avg_duration))
+}
+
+/// Compares another engine's group-by result against ours. Rows are matched after
+/// sorting both frames by `key`; every non-key column of `ours` must exist in
+/// `theirs` and agree within `tolerance` (relative, absolute below 1.0).
+/// Returns a description of each divergence found.
+fn check_equivalence(ours: &DataFrame, theirs: &DataFrame, key: &str, tolerance: f64) -> PolarsResult<Vec<String>> {
+    let ours = ours.clone().lazy().sort([key], Default::default()).collect()?;
+    let theirs = theirs.clone().lazy().sort([key], Default::default()).collect()?;
+    if ours.height() != theirs.height() {
+        return Ok(vec![format!("row count differs: {} vs {}", ours.height(), theirs.height())]);
+    }
+
+    let mut divergences = Vec::new();
+    let our_keys = ours.column(key)?.cast(&DataType::String)?;
+    let their_keys = theirs.column(key)?.cast(&DataType::String)?;
+    if !our_keys.equals(&their_keys) {
+        divergences.push(format!("group keys in '{}' differ", key));
+    }
+
+    for column in ours.get_columns().iter().filter(|c| c.name() != key) {
+        let Ok(other) = theirs.column(column.name()) else {
+            divergences.push(format!("column '{}' missing from reference result", column.name()));
+            continue;
+        };
+        let a = column.cast(&DataType::Float64)?;
+        let b = other.cast(&DataType::Float64)?;
+        for (row, (x, y)) in a.f64()?.into_iter().zip(b.f64()?.into_iter()).enumerate() {
+            let matches = match (x, y) {
+                (Some(x), Some(y)) => (x - y).abs() <= tolerance * x.abs().max(1.0),
+                (None, None) => true,
+                _ => false,
+            };
+            if !matches {
+                divergences.push(format!("'{}' row {} ({}): {:?} vs {:?}",
+                                         column.name(), row, our_keys.get(row)?, x, y));
+            }
+        }
+    }
+    Ok(divergences)
+}
+
+/// Stage names accepted by `--profile-stage`
+const PROFILE_STAGES: [&str; 6] = ["read", "count", "sort", "filter", "groupby", "pipeline"];
+
+/// Runs a single operation back-to-back for `duration` so an external profiler
+/// (VTune, perf, Process Explorer) sees almost nothing but that operation
+fn profile_stage(cfg: &BenchConfig, path: &str, stage: &str, duration: Duration) -> Result<(), BenchError> {
+    let group_col = cfg.group_key.as_str();
+    let value_col = cfg.value_column.as_str();
+    cfg.validate_input(path)?;
+    let scan = || LazyCsvReader::new(path).with_has_header(true).finish();
+    let df = scan()?.collect()?;
+    let aggs = [col(value_col).mean().alias(&format!("{}_mean", value_col))];
+    let predicate = cfg.filter_expr()?;
+
+    let operation: Box<dyn Fn() -> PolarsResult<DataFrame> + '_> = match stage {
+        "read" => Box::new(|| scan()?.collect()),
+        "count" => Box::new(|| df.clone().lazy().select([len()]).collect()),
+        "sort" => Box::new(|| df.clone().lazy().sort([value_col], Default::default()).collect()),
+        "filter" => Box::new(|| df.clone().lazy().filter(predicate.clone()).collect()),
+        "groupby" => Box::new(|| df.clone().lazy().group_by([col(group_col)]).agg(aggs.clone()).collect()),
+        "pipeline" => Box::new(|| {
+            scan()?
+                .sort([value_col], Default::default())
+                .filter(predicate.clone())
+                .group_by([col(group_col)])
+                .agg(aggs.clone())
+                .collect()
+        }),
+        other => {
+            return Err(BenchError::Config(format!(
+                "unknown --profile-stage {:?}; expected one of {:?}", other, PROFILE_STAGES
+            )))
+        }
+    };
+
+    say!("🔬 Profiling '{}' for {:.0?} (pid {})", stage, duration, std::process::id());
+    let start = Instant::now();
+    let mut iterations = 0u64;
+    while start.elapsed() < duration {
+        operation()?;
+        iterations += 1;
+    }
+    let elapsed = start.elapsed();
+    say!("✅ {} iterations in {:.3?} ({:.3?} per iteration)",
+             iterations, elapsed, elapsed / iterations.max(1) as u32);
+    Ok(())
+}
+
+/// `--seed-per-trial`: regenerates the CSV before every trial with seed `base + trial`, so
+/// each read + pipeline run sees fresh data instead of the warm, identical file. Generation
+/// is timed separately and never counted as operation time.
+fn run_seed_per_trial(run: &mut BenchRun, cfg: &'static BenchConfig, cli: &CliArgs, path: &'static str, trials: usize) -> Result<(), BenchError> {
+    let predicate = cfg.filter_expr()?;
+    let base_seed = cli.seed.unwrap_or_else(|| rand::thread_rng().gen());
+    say!("🌱 Fresh data per trial: {} trials, seeds {}..{}", trials, base_seed, base_seed.wrapping_add(trials as u64 - 1));
+
+    let mut rows = Vec::with_capacity(trials);
+    for trial in 0..trials {
+        let seed = base_seed.wrapping_add(trial as u64);
+        let gen_start = Instant::now();
+        generate_csv(path, cli, Some(seed))?;
+        let generation = gen_start.elapsed();
+        let predicate = predicate.clone();
+        let (_, operation) = time_operation(
+            run,
+            move || {
+                let scan = LazyCsvReader::new(path).with_has_header(true).finish()?;
+                pipeline_stages(scan, cfg, predicate.clone()).collect()
+            },
+            1,
+            &format!("Read + Pipeline (seed {})", seed)
+        )?;
+        rows.push((seed, generation, operation));
+    }
+    run.register_artifact(path);
+
+    say!("\n   {:<22} {:>14} {:>14}", "Seed", "Generation", "Operation");
+    for (seed, generation, operation) in &rows {
+        say!("   {:<22} {:>14.3?} {:>14.3?}", seed, generation, operation);
+    }
+    let generation: Vec<Duration> = rows.iter().map(|r| r.1).collect();
+    let operation: Vec<Duration> = rows.iter().map(|r| r.2).collect();
+    say!("   {:<22} {:>14.3?} {:>14.3?}", "Average",
+             generation.iter().sum::<Duration>() / trials as u32,
+             operation.iter().sum::<Duration>() / trials as u32);
+    say!("   Operation CV across datasets: {:.1}%", coefficient_of_variation(&operation) * 100.0);
+    Ok(())
+}
+
+const REPL_HELP: &str = "commands: sort <col> [desc] | filter <expr> | groupby <col> | head [n] | schema | reset | help | quit";
+
+/// `--repl`: loads the data once, then times commands read from stdin against the
+/// in-memory frame. `sort` and `filter` replace the current frame so they can be chained;
+/// `groupby` aggregates the value column without replacing it; `reset` restores the load.
+fn run_repl(run: &mut BenchRun, cfg: &'static BenchConfig, path: &str) -> PolarsResult<()> {
+    use std::io::BufRead;
+
+    let value_col = cfg.value_column.as_str();
+    cfg.validate_input(path)?;
+    let loaded = LazyCsvReader::new(path).with_has_header(true).finish()?.collect()?;
+    let mut current = loaded.clone();
+    say!("🧪 REPL over {} ({} rows); {}", path, current.height(), REPL_HELP);
+
+    let stdin = std::io::stdin();
+    loop {
+        say_inline!("polars> ");
+        flush_log();
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).unwrap_or(0) == 0 {
+            break;
+        }
+        let line = line.trim();
+        let (command, rest) = line.split_once(' ').map_or((line, ""), |(c, r)| (c, r.trim()));
+        let outcome = match command {
+            "" => continue,
+            "quit" | "exit" => break,
+            "help" => {
+                say!("{}", REPL_HELP);
+                continue;
+            }
+            "schema" => {
+                say!("{:?}", current.schema());
+                continue;
+            }
+            "reset" => {
+                current = loaded.clone();
+                say!("   Restored the loaded frame ({} rows)", current.height());
+                continue;
+            }
+            "head" => {
+                let n = rest.parse().unwrap_or(PREVIEW_ROWS as usize);
+                say!("{}", current.head(Some(n)));
+                continue;
+            }
+            "sort" => {
+                let mut parts = rest.split_whitespace();
+                let Some(column) = parts.next() else {
+                    say!("   usage: sort <col> [desc]");
+                    continue;
+                };
+                let options = SortMultipleOptions::default().with_order_descending(parts.next() == Some("desc"));
+                let (frame, column) = (current.clone(), column.to_string());
+                time_operation(run, move || frame.clone().lazy().sort([column.as_str()], options.clone()).collect(), 3, line)
+                    .map(|(df, _)| Some(df))
+            }
+            "filter" => match parse_filter(rest) {
+                Ok(predicate) => {
+                    let frame = current.clone();
+                    time_operation(run, move || frame.clone().lazy().filter(predicate.clone()).collect(), 3, line)
+                        .map(|(df, _)| Some(df))
+                }
+                Err(e) => {
+                    say!("   Invalid filter {:?}: {}", rest, e);
+                    continue;
+                }
+            },
+            "groupby" if !rest.is_empty() => time_operation(
+                run,
+                {
+                    let (frame, key) = (current.clone(), rest.to_string());
+                    move || {
+                        frame.clone().lazy()
+                            .group_by([col(&key)])
+                            .agg([
+                                len().alias("rows"),
+                                col(value_col).mean().alias(&format!("{}_mean", value_col)),
+                            ])
+                            .collect()
+                    }
+                },
+                3,
+                line,
+            )
+            .map(|(df, _)| {
+                say!("{}", df);
+                None
+            }),
+            _ => {
+                say!("   Unknown command {:?}; {}", line, REPL_HELP);
+                continue;
+            }
+        };
+        // Failures are already reported by time_operation; keep the session alive
+        if let Ok(Some(df)) = outcome {
+            say!("   {} rows", df.height());
+            current = df;
+        }
+    }
+    Ok(())
+}
+
+/// Seed `--verify-determinism` generates with when `--seed` is not given
+const DETERMINISM_SEED: u64 = 0;
+
+/// `--verify-determinism`: generates the data and runs the lazy pipeline twice with the
+/// same seed (or twice over `--input`), then compares the results: identical frames pass;
+/// the same rows in a different order are reported as unstable group-by output and fail
+/// unless `--ignore-order` is given; anything else is listed via `check_equivalence` and
+/// fails the run.
+fn verify_determinism(run: &mut BenchRun, cfg: &'static BenchConfig, cli: &CliArgs, path: &'static str) -> Result<(), BenchError> {
+    let group_col = cfg.group_key.as_str();
+    let predicate = cfg.filter_expr()?;
+    let seed = cli.seed.unwrap_or(DETERMINISM_SEED);
+    let mut results = Vec::new();
+    for attempt in 1..=2 {
+        if cfg.input.is_none() {
+            generate_csv(path, cli, Some(seed))?;
+            run.register_artifact(path);
+        }
+        cfg.validate_input(path)?;
+        let predicate = predicate.clone();
+        let (result, _) = time_operation(
+            run,
+            move || {
+                let scan = LazyCsvReader::new(path).with_has_header(true).finish()?;
+                pipeline_stages(scan, cfg, predicate.clone()).collect()
+            },
+            1,
+            &format!("Full Lazy Pipeline (run {})", attempt)
+        )?;
+        results.push(result);
+    }
+
+    let (first, second) = (&results[0], &results[1]);
+    say!("\n🔁 Determinism check (seed {}):", seed);
+    if first.equals_missing(second) {
+        say!("   ✅ Both runs produced identical results, row order included");
+        return Ok(());
+    }
+    if frame_checksum(first)? == frame_checksum(second)? {
+        say!("   ⚠️  Same rows in a different order: group-by output order is not stable.");
+        say!("      Sort the result or use maintain_order if downstream code depends on it.");
+        say!("   Run 1:\n{}\n   Run 2:\n{}", first, second);
+        if cli.ignore_order {
+            say!("   Passing anyway (--ignore-order)");
+            return Ok(());
+        }
+        return Err(polars_err!(ComputeError: "pipeline row order differs between two runs with seed {}", seed).into());
+    }
+    let divergences = check_equivalence(first, second, group_col, 0.0)?;
+    for d in &divergences {
+        say!("   ❌ {}", d);
+    }
+    Err(polars_err!(ComputeError: "pipeline results differ between two runs with seed {}", seed).into())
+}
+
+/// `--compare-lazy-eager-memory`: runs the read→sort→filter→group-by chain lazily, then
+/// eagerly with every intermediate frame kept alive, and prints the peak private-usage
+/// growth of each. Lazy runs first: memory the allocator keeps from it can only make the
+/// eager run look smaller, so the reported gap is conservative.
+fn compare_lazy_eager_memory(cfg: &BenchConfig, path: &str) -> Result<(), BenchError> {
+    let predicate = cfg.filter_expr()?;
+    cfg.validate_input(path)?;
+
+    let (lazy, lazy_peak, lazy_time) = peak_private_during(|| {
+        let scan = LazyCsvReader::new(path).with_has_header(true).finish()?;
+        pipeline_stages(scan, cfg, predicate.clone()).collect()
+    });
+    let lazy = lazy?;
+
+    let (eager, eager_peak, eager_time) = peak_private_during(|| -> PolarsResult<DataFrame> {
+        let loaded = LazyCsvReader::new(path).with_has_header(true).finish()?.collect()?;
+        let sorted = sort_stage(loaded.clone().lazy(), cfg).collect()?;
+        let filtered = filter_stage(sorted.clone().lazy(), predicate.clone()).collect()?;
+        let grouped = group_by_stage(filtered.clone().lazy(), cfg).collect()?;
+        // Intermediates stay alive until here, as in step-by-step eager code
+        drop((loaded, sorted, filtered));
+        Ok(grouped)
+    });
+    let eager = eager?;
+
+    let mb = |bytes: u64| bytes as f64 / 1024.0 / 1024.0;
+    say!("\n🧠 Lazy vs Eager Memory (read → sort → filter → group-by):");
+    say!("   {:<8} {:>16} {:>12} {:>8}", "Mode", "Peak growth", "Time", "Groups");
+    say!("   {:<8} {:>13.1} MB {:>12.3?} {:>8}", "Lazy", mb(lazy_peak), lazy_time, lazy.height());
+    say!("   {:<8} {:>13.1} MB {:>12.3?} {:>8}", "Eager", mb(eager_peak), eager_time, eager.height());
+    if lazy_peak > 0 {
+        say!("   Eager peak is {:.2}x the lazy peak", eager_peak as f64 / lazy_peak as f64);
+    }
+    Ok(())
+}
+
+/// `--row-sweep`: generates the data at each row count and runs the whole suite on it,
+/// then prints an operation × rows matrix of average times
+fn run_row_sweep(cfg: &'static BenchConfig, cli: &CliArgs, path: &'static str) -> Result<(), BenchError> {
+    let mut columns: Vec<(usize, Vec<StageTiming>)> = Vec::new();
+    let mut operations: Vec<String> = Vec::new();
+    for &rows in &cli.row_sweep {
+        say!("\n===== {} rows =====", rows);
+        let sized = CliArgs { rows, ..cli.clone() };
+        let mut run = BenchRun::new(&sized);
+        generate_csv(path, &sized, sized.seed)?;
+        run.register_artifact(path);
+        run_suite(&mut run, cfg, &sized, path)?;
+        for t in &run.timings {
+            if !operations.contains(&t.name) {
+                operations.push(t.name.clone());
+            }
+        }
+        if cli.cleanup {
+            run.cleanup_artifacts();
+        }
+        columns.push((rows, run.timings));
+    }
+
+    say!("\n📐 Scaling (avg ms by row count):");
+    let header: Vec<String> = columns.iter().map(|(rows, _)| format!("{:>12}", rows)).collect();
+    say!("   {:<36}{}", "Operation", header.join(""));
+    for op in &operations {
+        let cells: Vec<String> = columns
+            .iter()
+            .map(|(_, timings)| match timings.iter().find(|t| &t.name == op) {
+                Some(t) if t.error.is_none() => format!("{:>12.3}", t.avg.as_secs_f64() * 1000.0),
+                Some(_) => format!("{:>12}", "failed"),
+                None => format!("{:>12}", "-"),
+            })
+            .collect();
+        say!("   {:<36}{}", op, cells.join(""));
+    }
+    Ok(())
+}
+
+/// Reads which optimizer rewrites show up in an optimized plan's text: a scan carrying a
+/// `SELECTION` (predicate pushdown), a scan projecting fewer than all columns (projection
+/// pushdown), and CSE-generated `__POLARS_CSER` columns or shared `CACHE` nodes (CSE)
+fn plan_optimizations(plan: &str) -> [(&'static str, bool); 3] {
+    let predicate_pushdown = plan
+        .lines()
+        .any(|line| line.trim_start().starts_with("SELECTION:") && !line.contains("None"));
+    let projection_pushdown = plan.lines().any(|line| {
+        let line = line.trim_start();
+        line.starts_with("PROJECT") && !line.starts_with("PROJECT */")
+    });
+    let cse = plan.contains("__POLARS_CSER") || plan.contains("CACHE");
+    [
+        ("predicate_pushdown", predicate_pushdown),
+        ("projection_pushdown", projection_pushdown),
+        ("cse", cse),
+    ]
+}
+
+/// Stable 64-bit FNV-1a checksum of a frame's schema and contents. Rows are sorted by
+/// every column first so parallel execution order doesn't matter, and floats are
+/// hashed at 12 significant digits to absorb summation-order rounding noise.
+fn frame_checksum(df: &DataFrame) -> PolarsResult<u64> {
+    const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+    let mut hash = FNV_OFFSET;
+    let mut feed = |bytes: &[u8]| {
+        for b in bytes {
+            hash ^= *b as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    };
+
+    let sort_by: Vec<Expr> = df.get_column_names().into_iter().map(col).collect();
+    let sorted = df.clone().lazy().sort_by_exprs(sort_by, Default::default()).collect()?;
+    for column in sorted.get_columns() {
+        feed(column.name().as_bytes());
+        feed(column.dtype().to_string().as_bytes());
+        for row in 0..column.len() {
+            let text = match column.get(row)? {
+                AnyValue::Float64(v) => format!("{:.11e}", v),
+                AnyValue::Float32(v) => format!("{:.11e}", v),
+                other => other.to_string(),
+            };
+            feed(text.as_bytes());
+            feed(&[0x1f]);
+        }
+    }
+    Ok(hash)
+}
+
+/// Renders the `--output` report for the given stages; None for plain text
+fn render_report(
+    format: OutputFormat,
+    timings: &[StageTiming],
+    environment: &EnvironmentInfo,
+    checksums: &[(String, u64)],
+) -> Option<String> {
+    let avg_ms = |t: &StageTiming| t.avg.as_secs_f64() * 1000.0;
+    match format {
+        OutputFormat::Text => None,
+        OutputFormat::Tsv => Some(
+            timings
+                .iter()
+                .map(|t| match t.error {
+                    Some(_) => format!("{}\tNaN\t{}\n", t.name, t.working_set_mb),
+                    None => format!("{}\t{:.3}\t{}\n", t.name, avg_ms(t), t.working_set_mb),
+                })
+                .collect(),
+        ),
+        OutputFormat::Json => {
+            let stages: Vec<serde_json::Value> = timings
+                .iter()
+                .map(|t| {
+                    serde_json::json!({
+                        "operation": t.name,
+                        "trials": t.trials,
+                        "avg_ms": avg_ms(t),
+                        "working_set_mb": t.working_set_mb,
+                        "working_set_min_mb": t.working_set_range_mb.0,
+                        "working_set_max_mb": t.working_set_range_mb.1,
+                        "bytes_per_row": t.bytes_per_row,
+                        "normalized_avg_ms": environment.machine_factor.map(|f| avg_ms(t) / f),
+                        "status": if t.error.is_some() { "failed" } else { "ok" },
+                        "error": t.error,
+                    })
+                })
+                .collect();
+            let checksums: serde_json::Map<String, serde_json::Value> = checksums
+                .iter()
+                .map(|(name, hash)| (name.clone(), format!("{:016x}", hash).into()))
+                .collect();
+            let report = serde_json::json!({
+                "environment": {
+                    "cpu_model": environment.cpu_model,
+                    "logical_cores": environment.logical_cores,
+                    "physical_cores": environment.physical_cores,
+                    "total_ram_mb": environment.total_ram_mb,
+                    "os": environment.os,
+                    "machine_factor": environment.machine_factor,
+                },
+                "stages": stages,
+                "checksums": checksums,
+            });
+            Some(serde_json::to_string_pretty(&report).unwrap() + "\n")
+        }
+        OutputFormat::Markdown => {
+            let mut md = format!(
+                "## Polars Benchmark\n\n{} ({} logical cores, {} MB RAM), {}\n\n",
+                environment.cpu_model, environment.logical_cores, environment.total_ram_mb, environment.os
+            );
+            if let Some(factor) = environment.machine_factor {
+                md.push_str(&format!("Machine speed factor: {:.3} (normalized = avg / factor)\n\n", factor));
+            }
+            md.push_str("| Operation | Trials | Avg (ms) | Working Set (MB) |\n|---|---:|---:|---:|\n");
+            for t in timings {
+                let avg = match (&t.error, environment.machine_factor) {
+                    (Some(_), _) => "failed".to_string(),
+                    (None, Some(factor)) => format!("{:.3} ({:.3} normalized)", avg_ms(t), avg_ms(t) / factor),
+                    (None, None) => format!("{:.3}", avg_ms(t)),
+                };
+                md.push_str(&format!("| {} | {} | {} | {} |\n", t.name, t.trials, avg, t.working_set_mb));
+            }
+            for (name, hash) in checksums {
+                md.push_str(&format!("\n{} checksum: `{:016x}`\n", name, hash));
+            }
+            Some(md)
+        }
+    }
+}
+
+/// Writes a rendered report to stdout (`-`) or to the given file
+fn emit_report(destination: &str, content: &str) {
+    if destination == "-" {
+        say!();
+        let mut stdout = std::io::stdout().lock();
+        let _ = stdout.write_all(content.as_bytes()).and_then(|()| stdout.flush());
+    } else {
+        match std::fs::write(destination, content) {
+            Ok(()) => say!("📝 Report written to {}", destination),
+            Err(e) => eprintln!("❌ Failed to write report to {}: {}", destination, e),
+        }
+    }
+}
+
+/// Ratio above which a measured A-vs-B difference is worth recommending
+const RECOMMEND_SPEEDUP: f64 = 1.2;
+
+/// Turns the measured stages into run-specific advice, using simple thresholds on the
+/// ratios between related stages; stages that did not run (or failed) are skipped
+fn recommendations(timings: &[StageTiming]) -> Vec<String> {
+    let ms = |name: &str| {
+        timings
+            .iter()
+            .find(|t| t.name == name && t.error.is_none())
+            .map(|t| t.avg.as_secs_f64() * 1000.0)
+    };
+    let ratio = |slow: &str, fast: &str| Some(ms(slow)? / ms(fast)?);
+    let mut advice = Vec::new();
+
+    let steps = [("CSV Read & Load", "reading"), ("Sort", "sort"), ("Filter", "filter"), ("GroupBy + Aggregate", "group-by")];
+    let measured: Vec<(&str, f64)> = steps.iter().filter_map(|(name, label)| Some((*label, ms(name)?))).collect();
+    if measured.len() == steps.len() {
+        let individual: f64 = measured.iter().map(|(_, v)| v).sum();
+        if let Some(lazy) = ms("Full Lazy Pipeline") {
+            let (x, direction) = if lazy <= individual { (individual / lazy, "faster") } else { (lazy / individual, "slower") };
+            advice.push(format!("Lazy pipeline was {:.1}x {} than the individual operations ({:.1} ms vs {:.1} ms)",
+                                x, direction, lazy, individual));
+        }
+        let (label, worst) = measured.iter().copied().fold(("", 0.0), |a, b| if b.1 > a.1 { b } else { a });
+        let hint = match label {
+            "reading" => "consider Parquet, projection pushdown or fewer columns",
+            "sort" => "avoid sorting before aggregation unless order is needed",
+            "filter" => "push the filter into the scan (lazy) so fewer rows are materialized",
+            _ => "consider categorical keys",
+        };
+        advice.push(format!("{} dominates at {:.0}% of individual operation time; {}", label, 100.0 * worst / individual, hint));
+    }
+    if let Some(x) = ratio("GroupBy (Utf8 key)", "GroupBy (Categorical key)").filter(|&x| x >= RECOMMEND_SPEEDUP) {
+        advice.push(format!("Categorical group keys were {:.1}x faster than Utf8; cast string keys you group on", x));
+    }
+    if let Some(x) = ratio("GroupBy (unsorted input)", "GroupBy (sorted input)").filter(|&x| x >= RECOMMEND_SPEEDUP) {
+        advice.push(format!("Pre-sorted keys made group-by {:.1}x faster; keep data sorted by the key when it comes cheap", x));
+    }
+    if let Some(x) = ratio("Sort (chunked)", "Sort (rechunked)").filter(|&x| x >= RECOMMEND_SPEEDUP) {
+        advice.push(format!("Fragmented frames sorted {:.1}x slower; rechunk after many vstacks/appends", x));
+    }
+    if let (Some(as_string), Some(as_float)) = (ms("CSV Read (value as String)"), ms("CSV Read (value as Float64)")) {
+        let share = (as_float - as_string).max(0.0) / as_float;
+        if share >= 0.3 {
+            advice.push(format!("Float parsing is ~{:.0}% of the read; skip unneeded float columns or write fewer decimals", 100.0 * share));
+        }
+    }
+    if let Some(x) = ratio("Full Lazy Pipeline (no optimizations)", "Full Lazy Pipeline").filter(|&x| x >= RECOMMEND_SPEEDUP) {
+        advice.push(format!("The optimizer made the pipeline {:.1}x faster; prefer lazy queries over eager steps", x));
+    }
+    let failed = timings.iter().filter(|t| t.error.is_some()).count();
+    if failed > 0 {
+        advice.push(format!("{} stage(s) failed; their numbers are missing from the comparisons above", failed));
+    }
+    advice
+}
+
+/// Per-trial slope (as a share of the mean) beyond which a stage counts as drifting
+const DRIFT_THRESHOLD: f64 = 0.02;
+
+/// `--iterations-report`: each stage's trial durations in order, with the least-squares
+/// slope per trial relative to the mean. A positive drift suggests throttling, a negative
+/// one warmup (caches, allocator, page faults) not yet finished.
+fn print_iterations_report(timings: &[StageTiming]) {
+    say!("\n📈 Per-Trial Drift:");
+    for t in timings.iter().filter(|t| t.samples.len() >= 3) {
+        let ms: Vec<f64> = t.samples.iter().map(|d| d.as_secs_f64() * 1000.0).collect();
+        let n = ms.len() as f64;
+        let mean_x = (n - 1.0) / 2.0;
+        let mean_y = ms.iter().sum::<f64>() / n;
+        let (mut cov, mut var) = (0.0, 0.0);
+        for (i, y) in ms.iter().enumerate() {
+            let dx = i as f64 - mean_x;
+            cov += dx * (y - mean_y);
+            var += dx * dx;
+        }
+        let drift = cov / var / mean_y;
+        let trend = if drift > DRIFT_THRESHOLD {
+            "slowing (throttling?)"
+        } else if drift < -DRIFT_THRESHOLD {
+            "speeding up (warmup)"
+        } else {
+            "flat"
+        };
+        let sequence: Vec<String> = ms.iter().map(|v| format!("{:.2}", v)).collect();
+        say!("   • {}: {:+.1}%/trial, {}", t.name, drift * 100.0, trend);
+        say!("     [{}] ms", sequence.join(", "));
+    }
+}
+
+/// Writes every trial of every stage as long-format CSV: operation,trial_index,duration_ms
+fn write_raw_timings(path: &str, timings: &[StageTiming]) -> std::io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    writeln!(writer, "operation,trial_index,duration_ms")?;
+    for t in timings {
+        for (index, sample) in t.samples.iter().enumerate() {
+            writeln!(writer, "\"{}\",{},{:.6}", t.name.replace('"', "\"\""), index, sample.as_secs_f64() * 1000.0)?;
+        }
+    }
+    writer.flush()
+}
+
+const PLOT_DATA_FILE: &str = "bench_plot.dat";
+const PLOT_SCRIPT_FILE: &str = "bench_plot.gp";
+
+/// Writes `operation avg_ms` rows (failed stages omitted) plus a gnuplot script rendering
+/// them as a bar chart; `gnuplot bench_plot.gp` produces bench_plot.png
+fn write_plot_files(timings: &[StageTiming]) -> std::io::Result<()> {
+    let mut data = String::from("# operation\tavg_ms\n");
+    for t in timings.iter().filter(|t| t.error.is_none()) {
+        data.push_str(&format!("\"{}\"\t{:.3}\n", t.name.replace('"', "'"), t.avg.as_secs_f64() * 1000.0));
+    }
+    std::fs::write(PLOT_DATA_FILE, data)?;
+    let script = format!(
+        "set terminal pngcairo size 1400,700\n\
+         set output 'bench_plot.png'\n\
+         set title 'Polars benchmark: average time per operation'\n\
+         set ylabel 'avg (ms)'\n\
+         set style data histograms\n\
+         set style fill solid 0.8\n\
+         set boxwidth 0.8\n\
+         set xtics rotate by -45\n\
+         set grid ytics\n\
+         unset key\n\
+         plot '{}' using 2:xtic(1)\n",
+        PLOT_DATA_FILE
+    );
+    std::fs::write(PLOT_SCRIPT_FILE, script)
+}
+
+/// Prints per-operation timings of two suite runs side by side; failed stages show as
+/// "failed" and get no difference or ratio
+fn print_comparison_table(label_a: &str, a: &BenchRun, label_b: &str, b: &BenchRun) {
+    let ms = |t: &StageTiming| t.error.is_none().then(|| t.avg.as_secs_f64() * 1000.0);
+    let cell = |t: &StageTiming| match ms(t) {
+        Some(value) => format!("{:>9.3} ms", value),
+        None => "failed".to_string(),
+    };
+    say!("\n📊 File Comparison:");
+    say!("   A = {}", label_a);
+    say!("   B = {}", label_b);
+    say!("   {:<24} {:>12} {:>12} {:>12} {:>8}", "Operation", "A", "B", "B - A", "B / A");
+    for ta in &a.timings {
+        match b.timings.iter().find(|tb| tb.name == ta.name) {
+            Some(tb) => {
+                let (diff, ratio) = match (ms(ta), ms(tb)) {
+                    (Some(ms_a), Some(ms_b)) => (
+                        format!("{:>+9.3} ms", ms_b - ms_a),
+                        if ms_a > 0.0 { format!("{:.2}x", ms_b / ms_a) } else { "-".to_string() },
+                    ),
+                    _ => ("-".to_string(), "-".to_string()),
+                };
+                say!("   {:<24} {:>12} {:>12} {:>12} {:>8}", ta.name, cell(ta), cell(tb), diff, ratio);
+            }
+            None => say!("   {:<24} {:>12} {:>12}", ta.name, cell(ta), "-"),
+        }
+    }
+    for tb in b.timings.iter().filter(|tb| !a.timings.iter().any(|ta| ta.name == tb.name)) {
+        say!("   {:<24} {:>12} {:>12}", tb.name, "-", cell(tb));
+    }
+}
+
+/// Runs the individual operations and the full lazy pipeline over `path`,
+/// returning (individual operations result, lazy pipeline result)
+fn run_suite(
+    run: &mut BenchRun,
+    cfg: &'static BenchConfig,
+    cli: &CliArgs,
+    path: &'static str,
+) -> Result<(Option<DataFrame>, Option<DataFrame>), BenchError> {
+    let id_col = cfg.id_column.as_str();
+    let group_col = cfg.group_key.as_str();
+    let value_col = cfg.value_column.as_str();
+    let predicate = cfg.filter_expr()?;
+    cfg.validate_input(path)?;
+
+    say!("\n--- Testing Individual Operations (Forced Execution) ---");
+
+    // 2. Read CSV and force execution
+    let io_before = get_io_read_bytes();
+    let (df, full_read) = time_operation(
+        run,
+        move || {
+            LazyCsvReader::new(path)
+                .with_has_header(true)
+                .finish()?
+                .collect()
+        },
+        3,
+        "CSV Read & Load"
+    )?;
+    print_io_read("CSV Read & Load", io_before, 3);
+    print_memory_detailed("CSV Read & Load");
+    print_column_memory(&df);
+    run.dataset_rows = Some(df.height());
+    // Keep a handle on the loaded frame (cheap Arc clone) for stages that need the unsorted, unfiltered data
+    let loaded = df.clone();
+
+    // Partial read: stop the scan after --limit rows (preview / early-termination cost)
+    run.stage_group("Limited Read", |run| {
+        if let Some(limit) = cli.limit {
+            let (preview, partial_read) = time_operation(
+                run,
+                move || {
+                    LazyCsvReader::new(path)
+                        .with_has_header(true)
+                        .with_n_rows(Some(limit))
+                        .finish()?
+                        .collect()
+                },
+                3,
+                "CSV Read (limited)"
+            )?;
+            say!("   Read {} of {} rows in {:.3?} vs full read {:.3?} ({:.1}% of full-read time)",
+                     preview.height(), df.height(), partial_read, full_read,
+                     100.0 * partial_read.as_secs_f64() / full_read.as_secs_f64());
+            print_memory_detailed("CSV Read (limited)");
+        }
+        Ok(())
+    })?;
+
+    // Quote handling (--quoted-fields): the same rows with a quoted, comma/newline-laden category
+    run.stage_group("Quoted CSV Read", |run| {
+        if cli.quoted_fields {
+            with_retry(&format!("Writing {}", QUOTED_DATA_FILE), || write_quoted_csv(path, QUOTED_DATA_FILE))?;
+            run.register_artifact(QUOTED_DATA_FILE);
+            let (quoted, quoted_read) = time_operation(
+                run,
+                move || {
+                    LazyCsvReader::new(QUOTED_DATA_FILE)
+                        .with_has_header(true)
+                        .finish()?
+                        .collect()
+                },
+                3,
+                "CSV Read (quoted fields)"
+            )?;
+            if quoted.height() != df.height() {
+                polars_bail!(ComputeError: "quoted read produced {} rows, expected {}", quoted.height(), df.height());
+            }
+            say!("   Quote-parsing overhead: {:.3?} vs {:.3?} clean ({:.2}x)",
+                     quoted_read, full_read, quoted_read.as_secs_f64() / full_read.as_secs_f64());
+            print_memory_detailed("CSV Read (quoted fields)");
+        }
+        Ok(())
+    })?;
+
+    // Float parse cost: the same read with the value column forced to String (bytes copied,
+    // no float parsing) vs forced to Float64; everything else is parsed identically
+    run.stage_group("Float Parsing", |run| {
+        let read_value_as = move |dtype: DataType| {
+            let overwrite = Arc::new(Schema::from_iter([Field::new(value_col, dtype)]));
+            move || {
+                LazyCsvReader::new(path)
+                    .with_has_header(true)
+                    .with_dtype_overwrite(Some(overwrite.clone()))
+                    .finish()?
+                    .collect()
+            }
+        };
+        let (_, as_string) = time_operation(run, read_value_as(DataType::String), 3, "CSV Read (value as String)")?;
+        let (_, as_float) = time_operation(run, read_value_as(DataType::Float64), 3, "CSV Read (value as Float64)")?;
+        let parse_cost = as_float.saturating_sub(as_string);
+        say!("   Float parsing of {}: ~{:.3?} ({:.1}% of the Float64 read)",
+                 value_col, parse_cost, 100.0 * parse_cost.as_secs_f64() / as_float.as_secs_f64());
+        print_memory_detailed("Float Parsing");
+        Ok(())
+    })?;
+
+    // Delimiter choice (--compare-separators): identical rows written with each separator
+    run.stage_group("Separators", |run| {
+        if cli.compare_separators {
+            let mut results = Vec::new();
+            for (label, separator, file_name) in SEPARATORS {
+                let mut copy = df.clone();
+                let file = with_retry(&format!("Creating {}", file_name), || File::create(file_name))?;
+                CsvWriter::new(file).with_separator(separator).finish(&mut copy)?;
+                run.register_artifact(file_name);
+                let (_, elapsed) = time_operation(
+                    run,
+                    move || {
+                        LazyCsvReader::new(file_name)
+                            .with_has_header(true)
+                            .with_separator(separator)
+                            .finish()?
+                            .collect()
+                    },
+                    3,
+                    &format!("CSV Read ({} separated)", label)
+                )?;
+                let size_mb = std::fs::metadata(file_name).map_or(0.0, |m| m.len() as f64 / 1024.0 / 1024.0);
+                results.push((label, elapsed, size_mb));
+            }
+            let fastest = results.iter().map(|r| r.1).min().unwrap_or_default();
+            for (label, elapsed, size_mb) in &results {
+                say!("   {:<6} {:>10.3?} {:>8.1} MB/s ({:.2}x fastest)",
+                         label, elapsed, size_mb / elapsed.as_secs_f64(), elapsed.as_secs_f64() / fastest.as_secs_f64());
+            }
+            print_memory_detailed("Separators");
+        }
+        Ok(())
+    })?;
+
+    // Reader memory mode (--low-memory): default vs low_memory parsing, time and peak growth
+    run.stage_group("Low Memory Read", |run| {
+        if cli.low_memory {
+            let read = move |low_memory: bool| {
+                move || {
+                    LazyCsvReader::new(path)
+                        .with_has_header(true)
+                        .with_low_memory(low_memory)
+                        .finish()?
+                        .collect()
+                }
+            };
+            let mut rows = Vec::new();
+            for (label, low_memory) in [("default", false), ("low_memory", true)] {
+                let name = format!("CSV Read ({})", label);
+                let (_, avg) = time_operation(run, read(low_memory), 3, &name)?;
+                // Peak comes from one extra untimed read sampled on a background thread
+                let (frame, peak, _) = peak_private_during(read(low_memory));
+                drop(frame?);
+                rows.push((label, avg, peak));
+            }
+            for (label, avg, peak) in &rows {
+                say!("   {:<10} {:>10.3?}  peak +{:.1} MB", label, avg, *peak as f64 / 1024.0 / 1024.0);
+            }
+            print_memory_detailed("Low Memory Read");
+        }
+        Ok(())
+    })?;
+
+    // Chunk size sweep: same full read, varying the rows the CSV parser handles per chunk.
+    // The lazy scan has no chunk-size setting, so the sweep and its baseline read eagerly.
+    run.stage_group("Read Chunk Sizes", |run| {
+        if !cli.read_chunk_sizes.is_empty() {
+            let read = move |chunk_size: Option<usize>| {
+                move || {
+                    let options = CsvReadOptions::default().with_has_header(true);
+                    let options = match chunk_size {
+                        Some(rows) => options.with_chunk_size(rows),
+                        None => options,
+                    };
+                    options.try_into_reader_with_file_path(Some(path.into()))?.finish()
+                }
+            };
+            let (_, default_read) = time_operation(run, read(None), 3, "CSV Read (eager, default chunk)")?;
+            let mut curve = Vec::new();
+            for &chunk_size in &cli.read_chunk_sizes {
+                let name = format!("CSV Read (chunk {})", chunk_size);
+                let (_, elapsed) = time_operation(run, read(Some(chunk_size)), 3, &name)?;
+                curve.push((chunk_size, elapsed));
+            }
+            say!("   Chunk size curve (eager default read: {:.3?}):", default_read);
+            for (chunk_size, elapsed) in &curve {
+                say!("   {:>10} rows/chunk: {:>10.3?} ({:.2}x default)",
+                         chunk_size, elapsed, elapsed.as_secs_f64() / default_read.as_secs_f64());
+            }
+            print_memory_detailed("CSV Read (chunk sweep)");
+        }
+        Ok(())
+    })?;
+
+    // Head / tail preview straight from the file: head can stop early, tail must scan everything
+    run.stage_group("Head / Tail", |run| {
+        let scan = move || LazyCsvReader::new(path).with_has_header(true).finish();
+        let (head, _) = time_operation(
+            run,
+            move || scan()?.limit(PREVIEW_ROWS).collect(),
+            3,
+            "Head (lazy limit)"
+        )?;
+        let (tail, _) = time_operation(
+            run,
+            move || scan()?.tail(PREVIEW_ROWS).collect(),
+            3,
+            "Tail (lazy)"
+        )?;
+        say!("   Preview - first {} rows:\n{}", PREVIEW_ROWS, head);
+        say!("   Preview - last {} rows:\n{}", PREVIEW_ROWS, tail);
+        print_memory_detailed("Head / Tail");
+        Ok(())
+    })?;
+
+    // 3. Row count (near-trivial scan, establishes the measurement-overhead floor)
+    run.stage_group("Row Count", |run| {
+        let frame = df.clone();
+        let (count_df, _) = time_operation(
+            run,
+            move || {
+                frame.clone().lazy()
+                    .select([len().alias("rows")])
+                    .collect()
+            },
+            3,
+            "Row Count"
+        )?;
+        say!("   Rows counted: {}", count_df.column("rows")?.get(0)?);
+        print_memory_detailed("Row Count");
+        Ok(())
+    })?;
+
+    // Distinct group keys (string hashing cost scales with --category-width)
+    run.stage_group("Unique Categories", |run| {
+        let frame = df.clone();
+        let (unique_df, _) = time_operation(
+            run,
+            move || {
+                frame.clone().lazy()
+                    .select([col(group_col).unique()])
+                    .collect()
+            },
+            3,
+            "Unique Categories"
+        )?;
+        if let Ok(keys) = df.column(group_col)?.str() {
+            let avg_bytes = keys.into_iter().flatten().map(str::len).sum::<usize>() as f64 / keys.len().max(1) as f64;
+            say!("   {} distinct keys, avg key length {:.1} bytes", unique_df.height(), avg_bytes);
+        }
+        print_memory_detailed("Unique Categories");
+        Ok(())
+    })?;
+
+    // Null cleanup (only meaningful when the value column actually holds nulls)
+    run.stage_group("Null Cleanup", |run| {
+        let null_count = df.column(value_col)?.null_count();
+        if null_count > 0 {
+            say!("   Nulls in '{}': {} of {} rows", value_col, null_count, df.height());
+
+            let frame = df.clone();
+            let (filled_df, _) = time_operation(
+                run,
+                move || {
+                    frame.clone().lazy()
+                        .with_column(col(value_col).fill_null(lit(0.0)))
+                        .collect()
+                },
+                3,
+                "Fill Null"
+            )?;
+            say!("   Rows after fill_null: {} (nulls left: {})",
+                     filled_df.height(), filled_df.column(value_col)?.null_count());
+            print_memory_detailed("Fill Null");
+
+            let frame = df.clone();
+            let (dropped_df, _) = time_operation(
+                run,
+                move || frame.clone().lazy().drop_nulls(None).collect(),
+                3,
+                "Drop Nulls"
+            )?;
+            say!("   Rows after drop_nulls: {}", dropped_df.height());
+            print_memory_detailed("Drop Nulls");
+        }
+        Ok(())
+    })?;
+
+    // Conditional expression: bucket value into low/medium/high thirds of its observed range
+    run.stage_group("When/Then/Otherwise", |run| {
+        let values = df.column(value_col)?.cast(&DataType::Float64)?;
+        let (lo, hi) = (values.f64()?.min().unwrap_or(0.0), values.f64()?.max().unwrap_or(0.0));
+        let (low_cut, high_cut) = (lo + (hi - lo) / 3.0, lo + 2.0 * (hi - lo) / 3.0);
+        let frame = df.clone();
+        let (labeled, _) = time_operation(
+            run,
+            move || {
+                frame.clone().lazy()
+                    .with_column(
+                        when(col(value_col).gt(lit(high_cut)))
+                            .then(lit("high"))
+                            .when(col(value_col).gt(lit(low_cut)))
+                            .then(lit("medium"))
+                            .otherwise(lit("low"))
+                            .alias("value_band"),
+                    )
+                    .collect()
+            },
+            3,
+            "When/Then/Otherwise"
+        )?;
+        let bands = labeled.column("value_band")?.value_counts(true, false)?;
+        say!("   Bands (cuts at {:.1} / {:.1}):\n{}", low_cut, high_cut, bands);
+        print_memory_detailed("When/Then/Otherwise");
+        Ok(())
+    })?;
+
+    // Top-N by value: top_k (partial sort, never orders the remaining rows) vs a fully
+    // materialized descending sort followed by head
+    run.stage_group("Top N", |run| {
+        let frame = loaded.clone();
+        let (top, top_k_time) = time_operation(
+            run,
+            move || frame.clone().lazy().top_k(TOP_N, [col(value_col)], SortMultipleOptions::default().with_nulls_last(true)).collect(),
+            3,
+            "Top N (top_k)"
+        )?;
+        let frame = loaded.clone();
+        let (head, sort_head_time) = time_operation(
+            run,
+            move || {
+                let sorted = frame.clone().lazy()
+                    .sort([value_col], SortMultipleOptions::default().with_order_descending(true).with_nulls_last(true))
+                    .collect()?;
+                Ok(sorted.head(Some(TOP_N as usize)))
+            },
+            3,
+            "Top N (sort + head)"
+        )?;
+        let min_value = |df: &DataFrame| -> PolarsResult<Option<f64>> {
+            Ok(df.column(value_col)?.cast(&DataType::Float64)?.f64()?.min())
+        };
+        if top.height() != head.height() || min_value(&top)? != min_value(&head)? {
+            polars_bail!(ComputeError: "top_k and sort + head disagree on the top {} rows", TOP_N);
+        }
+        say!("   top_k {:.2}x faster than sort + head for the top {} of {} rows",
+                 sort_head_time.as_secs_f64() / top_k_time.as_secs_f64(), TOP_N, loaded.height());
+        print_memory_detailed("Top N");
+        Ok(())
+    })?;
+
+    // Random subset (train/test-split style), seeded from --seed for reproducible samples
+    run.stage_group("Sample", |run| {
+        let (frame, frac, seed) = (df.clone(), Series::new("frac", [SAMPLE_FRACTION]), cli.seed);
+        let (sampled, _) = time_operation(
+            run,
+            move || frame.sample_frac(&frac, false, true, seed),
+            3,
+            "Sample (10%)"
+        )?;
+        let expected = df.height() as f64 * SAMPLE_FRACTION;
+        if (sampled.height() as f64 - expected).abs() > 1.0 {
+            polars_bail!(ComputeError: "sample_frac({}) returned {} rows, expected ~{:.0}", SAMPLE_FRACTION, sampled.height(), expected);
+        }
+        say!("   Sampled {} of {} rows (seed {:?})", sampled.height(), df.height(), cli.seed);
+        print_memory_detailed("Sample (10%)");
+        Ok(())
+    })?;
+
+    // Row numbering: materializes a new sequential index column over the loaded frame
+    run.stage_group("Row Index", |run| {
+        let frame = loaded.clone();
+        let (indexed, _) = time_operation(
+            run,
+            move || frame.with_row_index("row_nr", None),
+            3,
+            "With Row Index"
+        )?;
+        let index = indexed.column("row_nr")?.cast(&DataType::UInt64)?;
+        let sequential = index.null_count() == 0
+            && index.u64()?.into_no_null_iter().eq(0..loaded.height() as u64);
+        if !sequential {
+            polars_bail!(ComputeError: "with_row_index did not produce 0..{}", loaded.height());
+        }
+        say!("   Index runs 0..{} over {} chunk(s)", loaded.height(), loaded.n_chunks());
+        print_memory_detailed("With Row Index");
+        Ok(())
+    })?;
+
+    // Running aggregate: cum_sum over id order is inherently sequential, unlike the reductions
+    run.stage_group("Cumulative Sum", |run| {
+        let frame = df.clone();
+        let (running, _) = time_operation(
+            run,
+            move || {
+                frame.clone().lazy()
+                    .sort([id_col], Default::default())
+                    .select([
+                        col(id_col),
+                        col(value_col).cast(DataType::Float64).cum_sum(false).alias("value_cum_sum"),
+                    ])
+                    .collect()
+            },
+            3,
+            "Cumulative Sum"
+        )?;
+        // Nulls leave the running total unchanged, so the last non-null entry is the grand total
+        let cum = running.column("value_cum_sum")?.f64()?;
+        let last = cum.into_iter().flatten().last().unwrap_or(0.0);
+        let total = df.column(value_col)?.cast(&DataType::Float64)?.f64()?.sum().unwrap_or(0.0);
+        let rel_diff = (last - total).abs() / total.abs().max(1.0);
+        if rel_diff > 1e-9 {
+            polars_bail!(ComputeError: "final running total {:.3} differs from independent sum {:.3} (relative {:.2e})",
+                         last, total, rel_diff);
+        }
+        say!("   Final running total {:.3} matches independent sum", last);
+        print_memory_detailed("Cumulative Sum");
+        Ok(())
+    })?;
+
+    // Lag: shift the value column LAG_ROWS down in id order; the first LAG_ROWS rows become null
+    run.stage_group("Shift / Lag", |run| {
+        let frame = df.clone();
+        let (lagged, _) = time_operation(
+            run,
+            move || {
+                frame.clone().lazy()
+                    .sort([id_col], Default::default())
+                    .select([
+                        col(id_col),
+                        col(value_col),
+                        col(value_col).shift(lit(LAG_ROWS)).alias("value_lag"),
+                    ])
+                    .collect()
+            },
+            3,
+            "Shift / Lag"
+        )?;
+        let boundary = lagged.column("value_lag")?.head(Some(LAG_ROWS as usize));
+        if boundary.null_count() != boundary.len() {
+            polars_bail!(ComputeError: "shift({}) left non-null values in the first rows: {}", LAG_ROWS, boundary);
+        }
+        say!("   First {} lagged rows are null as expected", LAG_ROWS);
+        print_memory_detailed("Shift / Lag");
+        Ok(())
+    })?;
+
+    // Hash join fan-out sweep: a fixed-size lookup table whose keys repeat `fanout` times,
+    // so every probe row matches exactly `fanout` rows (1:1 up to 1:many)
+    run.stage_group("Join Cardinality", |run| {
+        let mut curve = Vec::new();
+        for fanout in JOIN_FANOUTS {
+            let distinct = (JOIN_LOOKUP_ROWS / fanout) as i64;
+            let lookup = DataFrame::new(vec![
+                Series::new("join_key", (0..JOIN_LOOKUP_ROWS as i64).map(|i| i % distinct).collect::<Vec<_>>()),
+                Series::new("lookup_payload", (0..JOIN_LOOKUP_ROWS).map(|i| i as f64).collect::<Vec<_>>()),
+            ])?
+            .lazy();
+            let probe = df.clone().lazy()
+                .with_column((col(id_col).cast(DataType::Int64) % lit(distinct)).alias("join_key"));
+            let name = format!("Join (1:{})", fanout);
+            let (joined, elapsed) = time_operation(
+                run,
+                move || probe.clone().inner_join(lookup.clone(), col("join_key"), col("join_key")).collect(),
+                3,
+                &name
+            )?;
+            curve.push((distinct, fanout, elapsed, joined.height()));
+        }
+        say!("   {:>10} {:>7} {:>12} {:>12}", "Keys", "Fan-out", "Time", "Output rows");
+        for (distinct, fanout, elapsed, rows) in curve {
+            say!("   {:>10} {:>7} {:>12.3?} {:>12}", distinct, fanout, elapsed, rows);
+        }
+        print_memory_detailed("Join Cardinality");
+        Ok(())
+    })?;
+
+    // Time-series stages (only when the timestamp column is present)
+    run.stage_group("Time Series", |run| {
+        let ts_col = cfg.timestamp_column.as_str();
+        let dtype = match df.column(ts_col) {
+            Ok(column) => column.dtype().clone(),
+            Err(_) => return Ok(()),
+        };
+        if dtype != DataType::Int64 {
+            say!("   Skipping time-series stages: '{}' is {}, not Int64 epoch milliseconds", ts_col, dtype);
+            return Ok(());
+        }
+        // Both stages mark the column sorted, so sort it (untimed) unless it already is
+        let df = if df.column(ts_col)?.is_sorted(SortOptions::default())? {
+            df.clone()
+        } else {
+            say!("   '{}' is not sorted; sorting it before the time-series stages", ts_col);
+            df.sort([ts_col], Default::default())?
+        };
+
+        // As-of join: match each row to the nearest prior reference timestamp
+        let reference = build_reference_frame(&df, ts_col)?
+            .lazy()
+            .with_column(col(ts_col).set_sorted_flag(IsSorted::Ascending));
+        let frame = df.clone();
+        let (asof_df, _) = time_operation(
+            run,
+            move || {
+                frame.clone().lazy()
+                    .with_column(col(ts_col).set_sorted_flag(IsSorted::Ascending))
+                    .join(
+                        reference.clone(),
+                        [col(ts_col)],
+                        [col(ts_col)],
+                        JoinArgs::new(JoinType::AsOf(AsOfOptions {
+                            strategy: AsofStrategy::Backward,
+                            ..Default::default()
+                        })),
+                    )
+                    .collect()
+            },
+            3,
+            "As-Of Join"
+        )?;
+        say!("   Rows matched to a reference point: {} of {}",
+                 asof_df.height() - asof_df.column("ref_value")?.null_count(), asof_df.height());
+        print_memory_detailed("As-Of Join");
+
+        // Dynamic group-by: aggregate value into fixed 1-minute windows
+        let frame = df.clone();
+        let (windows_df, _) = time_operation(
+            run,
+            move || {
+                frame.clone().lazy()
+                    .with_column(
+                        col(ts_col)
+                            .cast(DataType::Datetime(TimeUnit::Milliseconds, None))
+                            .set_sorted_flag(IsSorted::Ascending),
+                    )
+                    .group_by_dynamic(
+                        col(ts_col),
+                        Vec::<Expr>::new(),
+                        DynamicGroupOptions {
+                            every: polars::prelude::Duration::parse("1m"),
+                            period: polars::prelude::Duration::parse("1m"),
+                            offset: polars::prelude::Duration::parse("0m"),
+                            ..Default::default()
+                        },
+                    )
+                    .agg([
+                        col(value_col).mean().alias(&format!("{}_mean", value_col)),
+                        len().alias("rows"),
+                    ])
+                    .collect()
+            },
+            3,
+            "GroupBy Dynamic (1m)"
+        )?;
+        say!("   Time windows: {}", windows_df.height());
+        print_memory_detailed("GroupBy Dynamic (1m)");
+        Ok(())
+    })?;
+
+    let individual = run.stage_group("Sort / Filter / GroupBy", |run| {
+        let mut df = df.clone();
+
+        // 4. Sort (force execution with multiple trials)
+        let frame = df.clone();
+        let (sorted_df, _) = time_operation(
+            run,
+            move || sort_stage(frame.clone().lazy(), cfg).collect(),
+            3,
+            "Sort"
+        )?;
+        df = sorted_df;
+        print_memory_detailed("Sort");
+
+        // 5. Filter (force execution with multiple trials)
+        let (frame, filter) = (df.clone(), predicate.clone());
+        let (filtered_df, _) = time_operation(
+            run,
+            move || filter_stage(frame.clone().lazy(), filter.clone()).collect(),
+            3,
+            "Filter"
+        )?;
+        say!("   Predicate {:?} kept {} of {} rows", predicate, filtered_df.height(), df.height());
+        df = filtered_df;
+        print_memory_detailed("Filter");
+
+        // 6. GroupBy + Aggregate (force execution with multiple trials)
+        let frame = df.clone();
+        let (grouped_df, _) = time_operation(
+            run,
+            move || group_by_stage(frame.clone().lazy(), cfg).collect(),
+            3,
+            "GroupBy + Aggregate"
+        )?;
+        df = grouped_df;
+        print_memory_detailed("GroupBy + Aggregate");
+        Ok(df)
+    })?;
+
+    // Reshape: widen into --wide scaled copies of the value column, then melt them back to long form
+    run.stage_group("Melt", |run| {
+        if let Some(width) = cli.wide {
+            let wide_cols: Vec<String> = (1..=width).map(|k| format!("{}_{}", value_col, k)).collect();
+            let wide = loaded.clone().lazy()
+                .select(
+                    std::iter::once(col(id_col))
+                        .chain(wide_cols.iter().enumerate().map(|(k, name)| {
+                            (col(value_col) * lit((k + 1) as f64)).alias(name)
+                        }))
+                        .collect::<Vec<_>>(),
+                )
+                .collect()?;
+
+            let frame = wide.clone();
+            let (long, _) = time_operation(
+                run,
+                move || {
+                    frame.clone().lazy()
+                        .melt(MeltArgs {
+                            id_vars: vec![id_col.into()],
+                            value_vars: wide_cols.iter().map(|c| c.as_str().into()).collect(),
+                            variable_name: Some("variable".into()),
+                            value_name: Some(value_col.into()),
+                            ..Default::default()
+                        })
+                        .collect()
+                },
+                3,
+                "Melt"
+            )?;
+            say!("   Melted {} rows x {} value columns into {} rows",
+                     wide.height(), width, long.height());
+            print_memory_detailed("Melt");
+        }
+        Ok(())
+    })?;
+
+    // Tie-heavy sort (--distinct-values): the few-level value column against a dense unique
+    // column of the same length, plus a stable sort whose tie order must follow id
+    // (generated rows are written in id order)
+    run.stage_group("Sort Ties", |run| {
+        if let Some(levels) = cli.distinct_values {
+            let mut unique: Vec<f64> = (0..loaded.height()).map(|i| i as f64).collect();
+            unique.shuffle(&mut StdRng::seed_from_u64(cli.seed.unwrap_or(0)));
+            let mut frame = loaded.clone();
+            frame.with_column(Series::new("unique_value", unique))?;
+            let tied = frame.clone();
+            let (_, tie_time) = time_operation(
+                run,
+                move || tied.clone().lazy().sort([value_col], Default::default()).collect(),
+                3,
+                "Sort (tie-heavy)"
+            )?;
+            let tied = frame.clone();
+            let (stable, _) = time_operation(
+                run,
+                move || {
+                    tied.clone().lazy()
+                        .sort([value_col], SortMultipleOptions::default().with_maintain_order(true))
+                        .collect()
+                },
+                3,
+                "Sort (tie-heavy, stable)"
+            )?;
+            let tied = frame.clone();
+            let (_, unique_time) = time_operation(
+                run,
+                move || tied.clone().lazy().sort(["unique_value"], Default::default()).collect(),
+                3,
+                "Sort (unique keys)"
+            )?;
+            say!("   {} distinct values: tie-heavy sort {:.2}x the unique-key sort",
+                     levels, tie_time.as_secs_f64() / unique_time.as_secs_f64());
+            let by_value_then_id = frame.clone().lazy().sort([value_col, id_col], Default::default()).collect()?;
+            if !stable.column(id_col)?.equals_missing(by_value_then_id.column(id_col)?) {
+                polars_bail!(ComputeError: "stable sort did not keep ties in id order");
+            }
+            say!("   Stable sort kept ties in input (id) order");
+            print_memory_detailed("Sort Ties");
+        }
+        Ok(())
+    })?;
+
+    // Same group-by on the raw frame and on one pre-sorted by the key (sorted flag set),
+    // which lets polars use its sorted-key path instead of hashing
+    run.stage_group("Sorted vs Unsorted GroupBy", |run| {
+        let aggs = [col(value_col).mean().alias(&format!("{}_mean", value_col))];
+        let presorted = loaded.clone().lazy()
+            .sort([group_col], Default::default())
+            .with_column(col(group_col).set_sorted_flag(IsSorted::Ascending))
+            .collect()?;
+        let (frame, frame_aggs) = (loaded.clone(), aggs.clone());
+        let (_, unsorted_time) = time_operation(
+            run,
+            move || frame.clone().lazy().group_by([col(group_col)]).agg(frame_aggs.clone()).collect(),
+            3,
+            "GroupBy (unsorted input)"
+        )?;
+        let (_, sorted_time) = time_operation(
+            run,
+            move || presorted.clone().lazy().group_by([col(group_col)]).agg(aggs.clone()).collect(),
+            3,
+            "GroupBy (sorted input)"
+        )?;
+        say!("   Sorted-input speedup: {:.2}x", unsorted_time.as_secs_f64() / sorted_time.as_secs_f64());
+        print_memory_detailed("GroupBy (sorted input)");
+        Ok(())
+    })?;
+
+    // Same group-by keyed on the raw Utf8 column and on a Categorical cast of it
+    run.stage_group("Categorical GroupBy", |run| {
+        let key_dtype = loaded.column(group_col)?.dtype().clone();
+        if key_dtype != DataType::String {
+            say!("   Skipping categorical group-by: '{}' is {}, not a string key", group_col, key_dtype);
+            return Ok(());
+        }
+        let aggs = [col(value_col).mean().alias(&format!("{}_mean", value_col))];
+        let frame = loaded.clone();
+        let (categorical, _) = time_operation(
+            run,
+            move || {
+                frame.clone().lazy()
+                    .with_column(col(group_col).cast(DataType::Categorical(None, Default::default())))
+                    .collect()
+            },
+            3,
+            "Cast to Categorical"
+        )?;
+        let (frame, frame_aggs) = (loaded.clone(), aggs.clone());
+        let (_, utf8_time) = time_operation(
+            run,
+            move || frame.clone().lazy().group_by([col(group_col)]).agg(frame_aggs.clone()).collect(),
+            3,
+            "GroupBy (Utf8 key)"
+        )?;
+        let frame = categorical.clone();
+        let (_, categorical_time) = time_operation(
+            run,
+            move || frame.clone().lazy().group_by([col(group_col)]).agg(aggs.clone()).collect(),
+            3,
+            "GroupBy (Categorical key)"
+        )?;
+        let mb = |df: &DataFrame| -> PolarsResult<f64> {
+            Ok(df.column(group_col)?.estimated_size() as f64 / 1024.0 / 1024.0)
+        };
+        say!("   Categorical speedup: {:.2}x; key column {:.2} MB -> {:.2} MB",
+                 utf8_time.as_secs_f64() / categorical_time.as_secs_f64(), mb(&loaded)?, mb(&categorical)?);
+        print_memory_detailed("GroupBy (Categorical key)");
+        Ok(())
+    })?;
+
+    // Nested output: collect every group's values into one list per group (a bare column in
+    // `agg` implodes to a list), building large list buffers instead of scalars
+    run.stage_group("GroupBy List", |run| {
+        let frame = loaded.clone();
+        let (lists, _) = time_operation(
+            run,
+            move || {
+                frame.clone().lazy()
+                    .group_by([col(group_col)])
+                    .agg([col(value_col).alias("values")])
+                    .collect()
+            },
+            3,
+            "GroupBy List Aggregation"
+        )?;
+        let groups = lists.column(group_col)?.cast(&DataType::String)?;
+        let lengths = lists.column("values")?.list()?.into_iter().map(|values| values.map_or(0, |v| v.len()));
+        for (group, length) in groups.str()?.into_iter().zip(lengths) {
+            say!("   {}: {} values", group.unwrap_or("null"), length);
+        }
+        print_memory_detailed("GroupBy List Aggregation");
+        Ok(())
+    })?;
+
+    // Aggregating derived expressions: the squares are evaluated inside the group-by, and the
+    // population variance E[x²] - E[x]² is checked against polars' own var(0)
+    run.stage_group("GroupBy Computed Expression", |run| {
+        let value = move || col(value_col).cast(DataType::Float64);
+        let frame = loaded.clone();
+        let (computed, _) = time_operation(
+            run,
+            move || {
+                frame.clone().lazy()
+                    .group_by([col(group_col)])
+                    .agg([
+                        (value() * value()).sum().alias("value_sq_sum"),
+                        ((value() * value()).mean() - value().mean() * value().mean()).alias("value_var_from_squares"),
+                        value().var(0).alias("value_var"),
+                    ])
+                    .collect()
+            },
+            3,
+            "GroupBy Computed Expression"
+        )?;
+        let derived = computed.column("value_var_from_squares")?.f64()?;
+        let builtin = computed.column("value_var")?.f64()?;
+        let worst = derived.into_iter().zip(builtin.into_iter())
+            .filter_map(|(a, b)| Some((a? - b?).abs() / b?.abs().max(1.0)))
+            .fold(0.0, f64::max);
+        say!("   {} groups; E[x²]-E[x]² vs var(0) worst relative difference {:.2e}", computed.height(), worst);
+        print_memory_detailed("GroupBy Computed Expression");
+        Ok(())
+    })?;
+
+    // Arrow interop: export every column as a polars-arrow array and rebuild the frame.
+    // The native flavor hands over the in-memory buffers; the standard flavor converts
+    // polars-specific layouts (e.g. string views) to plain Arrow types other systems read.
+    run.stage_group("Arrow Round Trip", |run| {
+        let mut frame = loaded.clone();
+        frame.as_single_chunk_par();
+        for (flavor, pl_flavor) in [("native", true), ("standard", false)] {
+            let name = format!("Arrow Round Trip ({})", flavor);
+            let source = frame.clone();
+            let (back, _) = time_operation(
+                run,
+                move || {
+                    let arrays: Vec<_> = source
+                        .get_columns()
+                        .iter()
+                        .map(|s| (s.name().to_string(), s.to_arrow(0, pl_flavor)))
+                        .collect();
+                    let columns = arrays
+                        .into_iter()
+                        .map(|(name, array)| Series::from_arrow(&name, array))
+                        .collect::<PolarsResult<Vec<_>>>()?;
+                    DataFrame::new(columns)
+                },
+                3,
+                &name
+            )?;
+            if back.shape() != frame.shape() {
+                polars_bail!(ComputeError: "{} changed the shape: {:?} -> {:?}", name, frame.shape(), back.shape());
+            }
+        }
+        print_memory_detailed("Arrow Round Trip");
+        Ok(())
+    })?;
+
+    // Worst-case hash aggregation: group by the unique id, so every row is its own group
+    run.stage_group("GroupBy id", |run| {
+        if cli.append_id_as_category {
+            let frame = loaded.clone();
+            let (id_groups, _) = time_operation(
+                run,
+                move || {
+                    frame.clone().lazy()
+                        .group_by([col(id_col)])
+                        .agg([col(value_col).mean().alias(&format!("{}_mean", value_col))])
+                        .collect()
+                },
+                3,
+                "GroupBy id (all unique)"
+            )?;
+            say!("   Groups: {} for {} rows", id_groups.height(), loaded.height());
+            print_memory_detailed("GroupBy id (all unique)");
+        }
+        Ok(())
+    })?;
+
+    say!("\n--- Testing Optimized Lazy Pipeline ---");
+
+    // Full lazy pipeline (the proper way)
+    let scan = with_retry(&format!("Opening {}", path), || {
+        LazyCsvReader::new(path)
+            .with_has_header(true)
+            .finish()
+    })?;
+    let lazy_pipeline = pipeline_stages(scan, cfg, predicate.clone());
+
+    // Show the optimized plan
+    say!("\n🧠 Optimized Query Plan:");
+    let optimized_plan = lazy_pipeline.describe_optimized_plan()?;
+    say!("{}", optimized_plan);
+    let applied: Vec<String> = plan_optimizations(&optimized_plan)
+        .iter()
+        .map(|(name, fired)| format!("{}={}", name, if *fired { "yes" } else { "no" }))
+        .collect();
+    say!("   optimizations: {}", applied.join(", "));
+
+    // Time plan optimization alone (lowering + pushdowns/CSE, no execution)
+    let (lazy_result, pipeline_time) = run.stage_group("Lazy Pipeline", |run| {
+        let pipeline = lazy_pipeline.clone();
+        let (_, planning_time) = time_operation(
+            run,
+            move || pipeline.clone().to_alp_optimized().map(|_| ()),
+            5,
+            "Plan Optimization"
+        )?;
+
+        // Time the full lazy execution
+        let pipeline = lazy_pipeline.clone();
+        let (lazy_result, pipeline_time) = time_operation(
+            run,
+            move || pipeline.clone().collect(),
+            5,
+            "Full Lazy Pipeline"
+        )?;
+        print_ram("Full Lazy Pipeline");
+        say!("   Planning share of pipeline time: {:.2}%",
+                 100.0 * planning_time.as_secs_f64() / pipeline_time.as_secs_f64());
+
+        // Same pipeline with the optimizer's rewrites switched off, common subplan and
+        // subexpression elimination included (type coercion stays on, the plan can't run
+        // without it)
+        if cli.no_optimize {
+            let unoptimized = lazy_pipeline.clone()
+                .with_predicate_pushdown(false)
+                .with_projection_pushdown(false)
+                .with_slice_pushdown(false)
+                .with_simplify_expr(false)
+                .with_comm_subplan_elim(false)
+                .with_comm_subexpr_elim(false);
+            let (_, unoptimized_time) = time_operation(
+                run,
+                move || unoptimized.clone().collect(),
+                5,
+                "Full Lazy Pipeline (no optimizations)"
+            )?;
+            say!("   Optimizer speedup: {:.2}x ({:.3?} unoptimized vs {:.3?} optimized)",
+                     unoptimized_time.as_secs_f64() / pipeline_time.as_secs_f64(), unoptimized_time, pipeline_time);
+            print_ram("Full Lazy Pipeline (no optimizations)");
+        }
+        Ok((lazy_result, pipeline_time))
+    })?.unzip();
+
+    // N threads collecting the pipeline at once, all contending for polars' global rayon pool
+    run.stage_group("Concurrency Test", |run| {
+        if let Some(threads) = cli.concurrency {
+            let name = format!("Pipeline x{} threads", threads);
+            let pipeline = lazy_pipeline.clone();
+            let (latencies, batch_time) = time_operation(
+                run,
+                move || {
+                    std::thread::scope(|scope| {
+                        let handles: Vec<_> = (0..threads)
+                            .map(|_| scope.spawn(|| {
+                                let start = Instant::now();
+                                pipeline.clone().collect().map(|_| start.elapsed())
+                            }))
+                            .collect();
+                        handles
+                            .into_iter()
+                            .map(|h| h.join().expect("pipeline thread panicked"))
+                            .collect::<PolarsResult<Vec<Duration>>>()
+                    })
+                },
+                3,
+                &name
+            )?;
+            let mean = latencies.iter().sum::<Duration>() / threads as u32;
+            let slowest = latencies.iter().max().copied().unwrap_or_default();
+            say!("   Aggregate throughput: {:.2} pipelines/s", threads as f64 / batch_time.as_secs_f64());
+            say!("   Per-thread latency (last trial): mean {:.3?}, max {:.3?}", mean, slowest);
+            for (i, latency) in latencies.iter().enumerate() {
+                say!("     thread {:>2}: {:.3?}", i, latency);
+            }
+            if let Some(single) = pipeline_time {
+                say!("   Contention: mean latency {:.2}x the single-thread pipeline ({:.3?})",
+                         mean.as_secs_f64() / single.as_secs_f64(), single);
+            }
+            print_memory_detailed(&name);
+        }
+        Ok(())
+    })?;
+
+    say!("\n--- Testing Separate vs Concurrent Collection ---");
+
+    // Two independent queries over the loaded frame
+    run.stage_group("Concurrent Collection", |run| {
+        let filter_query = loaded.clone().lazy().filter(predicate.clone());
+        let group_query = loaded.clone().lazy()
+            .group_by([col(group_col)])
+            .agg([col(value_col).mean().alias(&format!("{}_mean", value_col))]);
+
+        let queries = (filter_query.clone(), group_query.clone());
+        let (_, separate_time) = time_operation(
+            run,
+            move || Ok((queries.0.clone().collect()?, queries.1.clone().collect()?)),
+            5,
+            "Collect Separately"
+        )?;
+        let (_, concurrent_time) = time_operation(
+            run,
+            move || collect_all(vec![filter_query.clone(), group_query.clone()]),
+            5,
+            "Collect All (concurrent)"
+        )?;
+        say!("   collect_all vs separate: {:+.3} ms ({:.2}x speedup)",
+                 (concurrent_time.as_secs_f64() - separate_time.as_secs_f64()) * 1000.0,
+                 separate_time.as_secs_f64() / concurrent_time.as_secs_f64());
+        print_ram("Collect All (concurrent)");
+        Ok(())
+    })?;
+
+    run.stage_group("Glob Scan", |run| {
+        if let Some(shards) = cli.shards {
+            say!("\n--- Testing Multi-File Glob Scan ---");
+
+            for shard in write_shards(&loaded, shards)? {
+                run.register_artifact(&shard);
+            }
+            run.register_artifact(SHARD_DIR);
+            let pattern = format!("{}/*.csv", SHARD_DIR);
+
+            let glob = pattern.clone();
+            let (scanned, _) = time_operation(
+                run,
+                move || {
+                    LazyCsvReader::new(&glob)
+                        .with_has_header(true)
+                        .finish()?
+                        .collect()
+                },
+                3,
+                "Glob Scan (sharded CSV)"
+            )?;
+            say!("   Scanned {} rows from {} shards via {}", scanned.height(), shards, pattern);
+            print_memory_detailed("Glob Scan (sharded CSV)");
+        }
+        Ok(())
+    })?;
+
+    run.stage_group("Chunked Frames", |run| {
+        if cli.chunked {
+            say!("\n--- Testing Chunked vs Single-Chunk Frames ---");
+
+            let fragmented = fragment_frame(&loaded, 1000)?;
+            say!("   Fragmented frame: {} rows in {} chunks", fragmented.height(), fragmented.n_chunks());
+
+            let frame = fragmented.clone();
+            let (_, chunked_sort) = time_operation(
+                run,
+                move || frame.clone().lazy().sort([value_col], Default::default()).collect(),
+                3,
+                "Sort (chunked)"
+            )?;
+            let (frame, filter) = (fragmented.clone(), predicate.clone());
+            let (_, chunked_filter) = time_operation(
+                run,
+                move || frame.clone().lazy().filter(filter.clone()).collect(),
+                3,
+                "Filter (chunked)"
+            )?;
+
+            let (rechunked, _) = time_operation(
+                run,
+                move || {
+                    let mut single = fragmented.clone();
+                    single.rechunk();
+                    Ok(single)
+                },
+                3,
+                "Rechunk"
+            )?;
+            say!("   After rechunk: {} chunk(s)", rechunked.n_chunks());
+
+            let frame = rechunked.clone();
+            let (_, single_sort) = time_operation(
+                run,
+                move || frame.clone().lazy().sort([value_col], Default::default()).collect(),
+                3,
+                "Sort (rechunked)"
+            )?;
+            let filter = predicate.clone();
+            let (_, single_filter) = time_operation(
+                run,
+                move || rechunked.clone().lazy().filter(filter.clone()).collect(),
+                3,
+                "Filter (rechunked)"
+            )?;
+            say!("   Sort: chunked {:.3?} vs rechunked {:.3?} ({:.2}x)",
+                     chunked_sort, single_sort, chunked_sort.as_secs_f64() / single_sort.as_secs_f64());
+            say!("   Filter: chunked {:.3?} vs rechunked {:.3?} ({:.2}x)",
+                     chunked_filter, single_filter, chunked_filter.as_secs_f64() / single_filter.as_secs_f64());
+            print_memory_detailed("Chunked vs Single-Chunk");
+        }
+        Ok(())
+    })?;
+
+    // Collect chunking vs follow-on work: a filtered CSV scan collects into as many chunks
+    // as the reader produced; rechunking right after collect costs a copy but may pay off
+    // in the sort that follows
+    run.stage_group("Collect Rechunk", |run| {
+        let filter = predicate.clone();
+        let filtered_scan = move || -> PolarsResult<LazyFrame> {
+            Ok(LazyCsvReader::new(path).with_has_header(true).finish()?.filter(filter.clone()))
+        };
+        let scan = filtered_scan.clone();
+        let (collected, collect_time) = time_operation(
+            run,
+            move || scan()?.collect(),
+            3,
+            "Collect (default)"
+        )?;
+        let (rechunked, rechunk_time) = time_operation(
+            run,
+            move || {
+                let mut df = filtered_scan()?.collect()?;
+                df.rechunk();
+                Ok(df)
+            },
+            3,
+            "Collect + Rechunk"
+        )?;
+        say!("   Collected into {} chunk(s); rechunked to {}", collected.n_chunks(), rechunked.n_chunks());
+        let frame = collected.clone();
+        let (_, sort_default) = time_operation(
+            run,
+            move || frame.clone().lazy().sort([value_col], Default::default()).collect(),
+            3,
+            "Sort after Collect"
+        )?;
+        let frame = rechunked.clone();
+        let (_, sort_rechunked) = time_operation(
+            run,
+            move || frame.clone().lazy().sort([value_col], Default::default()).collect(),
+            3,
+            "Sort after Collect + Rechunk"
+        )?;
+        say!("   Collect + sort: {:.3?} default vs {:.3?} with rechunk",
+                 collect_time + sort_default, rechunk_time + sort_rechunked);
+        print_memory_detailed("Collect Rechunk");
+        Ok(())
+    })?;
+
+    run.stage_group("Parquet Sink", |run| {
+        if cli.sink {
+            say!("\n--- Testing Streaming Sink vs Collect-then-Write ---");
+
+            // Collect the whole result into memory, then write it out
+            let pipeline = lazy_pipeline.clone();
+            let collect_write = move || -> PolarsResult<()> {
+                let mut result = pipeline.clone().collect()?;
+                let file = File::create("out_collected.parquet")?;
+                ParquetWriter::new(file).finish(&mut result)?;
+                Ok(())
+            };
+            time_operation(run, collect_write.clone(), 3, "Collect + Write Parquet")?;
+            run.register_artifact("out_collected.parquet");
+            print_memory_detailed("Collect + Write Parquet");
+
+            // Stream batches straight to disk without materializing the result
+            let pipeline = lazy_pipeline.clone();
+            let sink = move || {
+                pipeline
+                    .clone()
+                    .sink_parquet(PathBuf::from("out.parquet"), ParquetWriteOptions::default())
+            };
+            time_operation(run, sink.clone(), 3, "Sink Parquet")?;
+            run.register_artifact("out.parquet");
+            print_memory_detailed("Sink Parquet");
+
+            // Peaks come from one extra untimed run of each, sampled on a background thread
+            let (written, collect_peak, _) = peak_private_during(collect_write);
+            written?;
+            let (sunk, sink_peak, _) = peak_private_during(sink);
+            sunk?;
+            say!("   Peak private growth: collect + write +{:.1} MB vs sink +{:.1} MB",
+                     collect_peak as f64 / 1024.0 / 1024.0, sink_peak as f64 / 1024.0 / 1024.0);
+        }
+        Ok(())
+    })?;
+
+    // Column pruning: the loaded data as Parquet, scanned whole vs for the value column only
+    run.stage_group("Parquet Column Pruning", |run| {
+        if cli.parquet_pruning {
+            let mut copy = loaded.clone();
+            let file = with_retry(&format!("Creating {}", PARQUET_DATA_FILE), || File::create(PARQUET_DATA_FILE))?;
+            ParquetWriter::new(file).finish(&mut copy)?;
+            run.register_artifact(PARQUET_DATA_FILE);
+            let scan = move || LazyFrame::scan_parquet(PARQUET_DATA_FILE, ScanArgsParquet::default());
+
+            let io_before = get_io_read_bytes();
+            let (_, all_columns) = time_operation(run, move || scan()?.collect(), 3, "Parquet Scan (all columns)")?;
+            print_io_read("Parquet Scan (all columns)", io_before, 3);
+
+            let io_before = get_io_read_bytes();
+            let (_, one_column) = time_operation(
+                run,
+                move || scan()?.select([col(value_col)]).collect(),
+                3,
+                &format!("Parquet Scan ({} only)", value_col)
+            )?;
+            print_io_read(&format!("Parquet Scan ({} only)", value_col), io_before, 3);
+
+            say!("   Pruning to {} of {} columns: {:.3?} vs {:.3?} ({:.2}x faster)",
+                     1, loaded.width(), one_column, all_columns,
+                     all_columns.as_secs_f64() / one_column.as_secs_f64());
+            print_memory_detailed("Parquet Column Pruning");
+        }
+        Ok(())
+    })?;
+
+    Ok((individual, lazy_result))
+}
+
+fn main() -> Result<(), BenchError> {
+    // Arguments and config live for the whole run; leaking them lets trial closures, which
+    // may outlive their stage on a detached worker (`--op-timeout`), borrow from them
+    let cli: &'static CliArgs = Box::leak(Box::new(parse_args()?));
+    if cli.report == "-" && cli.output != OutputFormat::Text {
+        LOG_TO_STDERR.store(true, Ordering::Relaxed);
+    }
+    let mut run = BenchRun::new(cli);
+    let result = run_benchmark(&mut run, cli);
+    // A failed run returns early from wherever it failed; clean up what it left behind here
+    if result.is_err() && cli.cleanup {
+        run.cleanup_artifacts();
+    }
+    result
+}
+
+/// Everything after argument parsing; artifacts registered on `run` are removed by `main`
+/// with `--cleanup` even if this fails
+fn run_benchmark(run: &mut BenchRun, cli: &'static CliArgs) -> Result<(), BenchError> {
+    say!("🚀 Starting Polars Performance Benchmark\n");
+    if cli.normalize {
+        run.environment.machine_factor = Some(calibrate_machine());
+    }
+    // Installed after calibration so an interrupted report carries the machine factor too
+    install_interrupt_handler(cli.output, cli.report.clone(), run.environment.clone());
+    run.environment.print();
+
+    let cfg: &'static BenchConfig = Box::leak(Box::new(load_config(cli)?));
+    if cli.quoted_fields && cfg.input.is_some() {
+        return Err(BenchError::Config("--quoted-fields rewrites the generated CSV and can't be combined with --input".to_string()));
+    }
+
+    if cli.verify_golden {
+        say!("🔍 Verifying operation stages against the golden dataset...");
+        verify_golden(cli.seed.unwrap_or(GOLDEN_SEED))?;
+        say!("✅ Golden dataset results match");
+        return Ok(());
+    }
+
+    if cli.compare_files {
+        let [path_a, path_b] = cli.inputs.as_slice() else {
+            return Err(BenchError::Config("--compare-files requires exactly two --input paths".to_string()));
+        };
+        let mut run_b = BenchRun::new(cli);
+        say!("\n===== A: {} =====", path_a);
+        run_suite(run, cfg, cli, path_a)?;
+        say!("\n===== B: {} =====", path_b);
+        run_suite(&mut run_b, cfg, cli, path_b)?;
+        print_comparison_table(path_a, run, path_b, &run_b);
+        if cli.cleanup {
+            run.cleanup_artifacts();
+            run_b.cleanup_artifacts();
+        }
+        return Ok(());
+    }
+
+    let path = cfg.data_path();
+
+    if let Some(stage) = &cli.profile_stage {
+        if cfg.input.is_none() {
+            generate_csv(path, cli, cli.seed)?;
+            run.register_artifact(path);
+        }
+        profile_stage(cfg, path, stage, cli.duration)?;
+        if cli.cleanup {
+            run.cleanup_artifacts();
+        }
+        return Ok(());
+    }
+
+    if cli.verify_determinism {
+        verify_determinism(run, cfg, cli, path)?;
+        if cli.cleanup {
+            run.cleanup_artifacts();
+        }
+        return Ok(());
+    }
+
+    if cli.compare_lazy_eager_memory {
+        if cfg.input.is_none() {
+            generate_csv(path, cli, cli.seed)?;
+            run.register_artifact(path);
+        }
+        compare_lazy_eager_memory(cfg, path)?;
+        if cli.cleanup {
+            run.cleanup_artifacts();
+        }
+        return Ok(());
+    }
+
+    if cli.repl {
+        if cfg.input.is_none() {
+            generate_csv(path, cli, cli.seed)?;
+            run.register_artifact(path);
+        }
+        run_repl(run, cfg, path)?;
+        if cli.cleanup {
+            run.cleanup_artifacts();
+        }
+        return Ok(());
+    }
+
+    if !cli.row_sweep.is_empty() {
+        if cfg.input.is_some() {
+            return Err(BenchError::Config("--row-sweep generates synthetic data and can't be combined with --input".to_string()));
+        }
+        return run_row_sweep(cfg, cli, path);
+    }
+
+    if let Some(trials) = cli.seed_per_trial {
+        if cfg.input.is_some() {
+            return Err(BenchError::Config("--seed-per-trial regenerates synthetic data and can't be combined with --input".to_string()));
+        }
+        run_seed_per_trial(run, cfg, cli, path, trials)?;
+        if cli.cleanup {
+            run.cleanup_artifacts();
+        }
+        return Ok(());
+    }
+
+    // 1. Generate CSV (skipped when benchmarking an existing input file)
+    if cfg.input.is_none() {
+        say!("📝 Generating CSV data...");
+        let start = Instant::now();
+        generate_csv(path, cli, cli.seed)?;
+        run.register_artifact(path);
+        say!("✅ CSV generated in: {:.3?}", start.elapsed());
+        if let Ok(meta) = std::fs::metadata(path) {
+            say!("   File size: {:.2} MB", meta.len() as f64 / 1024.0 / 1024.0);
+        }
+        if cfg.filter.is_none() {
+            let kept = (cli.value_max - cfg.filter_threshold) / (cli.value_max - cli.value_min);
+            say!("   Values in [{}, {}): filter > {} keeps ~{:.1}% of rows",
+                     cli.value_min, cli.value_max, cfg.filter_threshold, 100.0 * kept.clamp(0.0, 1.0));
+        }
+        print_memory_detailed("CSV Generation");
+    } else {
+        say!("📂 Using input file: {}", path);
+    }
+
+    let (individual, lazy_result) = run_suite(run, cfg, cli, path)?;
+
+    say!("\n📊 Final Results:");
+    let mut checksums = Vec::new();
+    if let Some(df) = &individual {
+        say!("Individual operations result:\n{}", df);
+        checksums.push(("individual_operations".to_string(), frame_checksum(df)?));
+    }
+    if let Some(df) = &lazy_result {
+        say!("\nLazy pipeline result:\n{}", df);
+        checksums.push(("lazy_pipeline".to_string(), frame_checksum(df)?));
+    }
+    say!("\n🔑 Result checksums (stable across runs with the same --seed):");
+    for (name, hash) in &checksums {
+        say!("   {}: {:016x}", name, hash);
+    }
+
+    let failed: Vec<&StageTiming> = run.timings.iter().filter(|t| t.error.is_some()).collect();
+    if !failed.is_empty() {
+        say!("\n❌ Failed stages ({}):", failed.len());
+        for t in failed {
+            say!("   • {}: {}", t.name, t.error.as_deref().unwrap_or_default());
+        }
+    }
+
+    if let (Some(reference), Some(df)) = (&cli.reference_result, &individual) {
+        say!("\n🔍 Equivalence check against {}:", reference);
+        let theirs = LazyCsvReader::new(reference)
+            .with_has_header(true)
+            .finish()?
+            .collect()?;
+        let divergences = check_equivalence(df, &theirs, &cfg.group_key, cli.tolerance)?;
+        if divergences.is_empty() {
+            say!("   ✅ Results match (tolerance {:e})", cli.tolerance);
+        } else {
+            for d in &divergences {
+                say!("   ❌ {}", d);
+            }
+            return Err(polars_err!(ComputeError: "{} divergence(s) from the reference result", divergences.len()).into());
+        }
+    }
+
+    if cli.iterations_report {
+        print_iterations_report(&run.timings);
+    }
+
+    let total = run.started.elapsed();
+    let measured = run.measured_total();
+    say!("\n⏱️  Run Time Accounting:");
+    for t in &run.timings {
+        if t.error.is_some() {
+            say!("   • {}: failed after {} trial(s)", t.name, t.trials);
+            continue;
+        }
+        match run.environment.machine_factor {
+            Some(factor) => say!("   • {}: {:.3?} ({} x {:.3?}, normalized {:.3?})", t.name,
+                                     t.samples.iter().sum::<Duration>(), t.samples.len(), t.avg, t.avg.div_f64(factor)),
+            None => say!("   • {}: {:.3?} ({} x {:.3?})", t.name, t.samples.iter().sum::<Duration>(), t.samples.len(), t.avg),
+        }
+    }
+    say!("   Total wall-clock: {:.3?}", total);
+    say!("   Measured operations: {:.3?} ({:.1}%)",
+             measured, 100.0 * measured.as_secs_f64() / total.as_secs_f64());
+    say!("   Unmeasured overhead (generation, clones, printing): {:.3?}",
+             total.saturating_sub(measured));
+
+    let advice = recommendations(&run.timings);
+    if !advice.is_empty() {
+        say!("\n💡 Recommendations:");
+        for line in advice {
+            say!("• {}", line);
+        }
+    }
+
+    say!("\n📋 SUMMARY:");
+    say!("• Individual operations: Each step forced to execute separately");
+    say!("• Row Count: Cheapest possible scan, the baseline for measurement overhead");
+    say!("• Lazy pipeline: All operations optimized and executed together");
+    say!("• Memory metrics explained:");
+    say!("  - Working Set: Physical RAM currently used by process");
+    say!("  - Private Usage: Actual memory allocated to process (most accurate)");
+    say!("  - Pagefile Usage: Virtual memory used (includes swapped memory)");
+    say!("  - Peak Working Set: Highest physical RAM usage during execution");
+    say!("• Bytes/row: private-usage growth per stage normalized by row count, comparable across dataset sizes");
+    say!("• Disk read bytes: a warm OS page cache shows far fewer bytes on Linux repeat runs");
+    say!("• Chunk sizes (--read-chunk-size 10000,50000,...): full CSV read per parser chunk size, relative to the default");
+    say!("• Concurrency (--concurrency-test --concurrent N): N threads share one rayon pool, so per-thread latency grows with N");
+    say!("• Arrow round trip: native flavor is near zero-copy; standard flavor pays for converting to plain Arrow types");
+    say!("• Sink Parquet (--sink): streams the pipeline to disk; compare its peak memory with Collect + Write");
+    say!("• --cooldown-ms <ms> [--cooldown-between-trials]: idles before stages/trials; slower run, less thermal drift");
+    say!("• --op-timeout <secs>: a trial running longer fails its stage (kept going with --continue)");
+    say!("• Failures: --fail-fast (default) aborts the run; --continue skips the rest of the failed group");
+    say!("• Timing uses averages across multiple runs for accuracy");
+    say!("• The lazy pipeline should be significantly faster due to optimizations (--no-optimize measures the gap)");
+    say!("\n💡 External Profiling Options:");
+    say!("• Process Explorer: Real-time memory monitoring");
+    say!("• Windows Performance Monitor: Detailed system metrics");
+    say!("• For Linux: heaptrack, Valgrind massif");
+    say!("• cargo bench: criterion statistics (CIs, outliers, regressions) for sort / filter / group-by");
+    say!("• Use --profile-stage <{}> --duration <secs> to loop one operation under a profiler",
+             PROFILE_STAGES.join("|"));
+
+    if let Some(content) = render_report(cli.output, &run.timings, &run.environment, &checksums) {
+        emit_report(&cli.report, &content);
+        if cli.report != "-" {
+            run.register_artifact(&cli.report);
+        }
+    }
+    if let Some(raw_path) = &cli.raw_timings {
+        match write_raw_timings(raw_path, &run.timings) {
+            Ok(()) => {
+                say!("📝 Per-trial timings written to {}", raw_path);
+                run.register_artifact(raw_path);
+            }
+            Err(e) => eprintln!("❌ Failed to write raw timings to {}: {}", raw_path, e),
+        }
+    }
+    if cli.comparison_plot {
+        match write_plot_files(&run.timings) {
+            Ok(()) => {
+                say!("📈 Plot data written to {} (render with: gnuplot {})", PLOT_DATA_FILE, PLOT_SCRIPT_FILE);
+                run.register_artifact(PLOT_DATA_FILE);
+                run.register_artifact(PLOT_SCRIPT_FILE);
+            }
+            Err(e) => eprintln!("❌ Failed to write plot files: {}", e),
+        }
+    }
+
+    if cli.cleanup {
+        say!();
+        run.cleanup_artifacts();
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stages(seed: u64) -> (DataFrame, DataFrame, DataFrame) {
+        golden_stages(golden_frame(seed).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn golden_sort_orders_by_value() {
+        for seed in 0..4 {
+            let (sorted, _, _) = stages(seed);
+            assert_eq!(golden_ids(&sorted).unwrap(), [0, 6, 7, 3, 1, 5, 4, 2].map(Some));
+            let values: Vec<Option<f64>> = sorted.column("value").unwrap().f64().unwrap().into_iter().collect();
+            assert_eq!(values, [100.0, 200.0, 400.0, 550.0, 600.0, 700.0, 800.0, 900.0].map(Some));
+        }
+    }
+
+    #[test]
+    fn golden_filter_keeps_values_above_500() {
+        for seed in 0..4 {
+            let (_, filtered, _) = stages(seed);
+            assert_eq!(filtered.height(), 5);
+            assert_eq!(golden_ids(&filtered).unwrap(), [3, 1, 5, 4, 2].map(Some));
+        }
+    }
+
+    #[test]
+    fn golden_group_by_means_per_category() {
+        for seed in 0..4 {
+            let (_, _, grouped) = stages(seed);
+            assert_eq!(
+                golden_group_rows(&grouped).unwrap(),
+                [
+                    (Some("Category1"), Some(3.5), Some(800.0)),
+                    (Some("Category2"), Some(2.5), Some(700.0)),
+                    (Some("Category3"), Some(3.0), Some(550.0)),
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn filter_parses_every_operator() {
+        for (op, expected) in [
+            (">", col("value").gt(lit(5.0))),
+            (">=", col("value").gt_eq(lit(5.0))),
+            ("<", col("value").lt(lit(5.0))),
+            ("<=", col("value").lt_eq(lit(5.0))),
+            ("==", col("value").eq(lit(5.0))),
+            ("!=", col("value").neq(lit(5.0))),
+        ] {
+            assert_eq!(parse_filter(&format!("value {} 5", op)).unwrap(), expected, "operator {}", op);
+        }
+    }
+
+    #[test]
+    fn filter_and_binds_tighter_than_or() {
+        assert_eq!(
+            parse_filter("a > 1 or b > 2 and c > 3").unwrap(),
+            col("a").gt(lit(1.0)).or(col("b").gt(lit(2.0)).and(col("c").gt(lit(3.0)))),
+        );
+        assert_eq!(
+            parse_filter("a > 1 and b > 2 or c > 3").unwrap(),
+            col("a").gt(lit(1.0)).and(col("b").gt(lit(2.0))).or(col("c").gt(lit(3.0))),
+        );
+    }
+
+    #[test]
+    fn filter_quoted_strings_keep_separators_and_operators() {
+        assert_eq!(parse_filter(r#"category == "Category1""#).unwrap(), col("category").eq(lit("Category1")));
+        assert_eq!(
+            parse_filter(r#"category == "salt and pepper" or category != "this or that""#).unwrap(),
+            col("category").eq(lit("salt and pepper")).or(col("category").neq(lit("this or that"))),
+        );
+        assert_eq!(parse_filter(r#"label == "a >= b""#).unwrap(), col("label").eq(lit("a >= b")));
+    }
+
+    #[test]
+    fn filter_rejects_malformed_clauses() {
+        for text in [
+            "value",
+            "value 5",
+            "> 5",
+            "value >",
+            "value > five",
+            r#"category == "unterminated"#,
+            "value > 1 and ",
+            "value > 1 or ",
+        ] {
+            assert!(parse_filter(text).is_err(), "{:?} should not parse", text);
+        }
+    }
+
+    #[test]
+    fn golden_frame_is_seeded() {
+        assert!(golden_frame(1).unwrap().equals(&golden_frame(1).unwrap()));
+        let shuffles: Vec<Vec<Option<i64>>> = (0..4).map(|seed| golden_ids(&golden_frame(seed).unwrap()).unwrap()).collect();
+        assert!(shuffles.windows(2).any(|pair| pair[0] != pair[1]));
+    }
+}